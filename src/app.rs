@@ -1,7 +1,12 @@
-use crate::backend::pli_segmenter::process_text;
+use crate::backend::lexicon::Lexicon;
+use crate::backend::pli_segmenter::{SegOptions, SyllableMode, process_document};
+use crate::backend::{
+    compound, conjugation, formula, lemma, morphology, outline, phonotactics, segment_id,
+};
 use crate::components::input_ui::InputUI;
 use crate::components::result_ui::ResultUI;
 use leptos::prelude::*; // Use prelude for Leptos 0.7+
+use std::sync::Arc;
 
 #[component]
 pub fn App() -> impl IntoView {
@@ -9,9 +14,39 @@ pub fn App() -> impl IntoView {
     // create_signal remains the standard way to get (ReadSignal, WriteSignal)
     let (text, set_text) = signal("1. Tena samayena buddho bhagavā verañjāyaṃ viharati naḷerupucimandamūle mahatā bhikkhusaṅghena saddhiṃ pañcamattehi bhikkhusatehi. Assosi kho verañjo brāhmaṇo – ‘‘samaṇo khalu, bho, gotamo sakyaputto sakyakulā pabbajito verañjāyaṃ viharati naḷerupucimandamūle mahatā bhikkhusaṅghena saddhiṃ pañcamattehi bhikkhusatehi. Taṃ kho pana bhavantaṃ gotamaṃ evaṃ kalyāṇo kittisaddo abbhuggato – ‘itipi so bhagavā arahaṃ sammāsambuddho vijjācaraṇasampanno sugato lokavidū anuttaro purisadammasārathi satthā devamanussānaṃ buddho bhagavā [bhagavāti (syā.), dī. ni. 1.157, abbhuggatākārena pana sameti]. So imaṃ lokaṃ sadevakaṃ samārakaṃ sabrahmakaṃ sassamaṇabrāhmaṇiṃ pajaṃ sadevamanussaṃ sayaṃ abhiññā sacchikatvā pavedeti. So dhammaṃ deseti ādikalyāṇaṃ majjhekalyāṇaṃ pariyosānakalyāṇaṃ sātthaṃ sabyañjanaṃ; kevalaparipuṇṇaṃ parisuddhaṃ brahmacariyaṃ pakāseti; sādhu kho pana tathārūpānaṃ arahataṃ dassanaṃ hotī’’’ti.".to_string());
 
+    // Headword list; the bundled one until the user loads their own
+    let (lexicon, set_lexicon) = signal(Arc::new(Lexicon::bundled().clone()));
+
+    // SuttaCentral uid used for segment ids; the default text opens the
+    // Vinaya's first pārājika
+    let (uid, set_uid) = signal("pli-tv-bu-vb-pj1".to_string());
+
     // 2. State: Derived Data (Memoized)
     // FIX: Replaced create_memo(...) with Memo::new(...)
-    let processing_result = Memo::new(move |_| process_text(&text.get()).to_owned_data());
+    let options = SegOptions {
+        syllables: SyllableMode::SubTokens,
+        sandhi: true,
+        expand_elisions: true,
+        ..Default::default()
+    };
+    let orthography = options.orthography.clone();
+    let processing_result = Memo::new(move |_| {
+        let options = SegOptions {
+            lexicon: lexicon.get(),
+            ..options.clone()
+        };
+        let mut result = process_document(&text.get(), &options);
+        let lexicon = &options.lexicon;
+        compound::annotate(&mut result, lexicon);
+        morphology::annotate(&mut result, lexicon);
+        conjugation::annotate(&mut result);
+        lemma::annotate(&mut result, lexicon);
+        formula::annotate(&mut result);
+        segment_id::assign(&mut result, &uid.get());
+        outline::annotate(&mut result);
+        phonotactics::annotate(&mut result);
+        result
+    });
 
     // 3. View Layout
     view! {
@@ -19,11 +54,11 @@ pub fn App() -> impl IntoView {
             <h1 style="text-align: center; color: #333;">"Wasm Auto-Segmenter"</h1>
 
             // Component: Input
-            <InputUI set_text=set_text />
+            <InputUI set_text=set_text set_lexicon=set_lexicon uid=uid set_uid=set_uid />
 
             // Component: Output
             // .into() converts the Memo<SegmentationResult> into a Signal<SegmentationResult>
-            <ResultUI data=processing_result.into() />
+            <ResultUI data=processing_result.into() orthography=orthography />
         </main>
     }
 }
@@ -0,0 +1,153 @@
+use crate::backend::numeral;
+use crate::backend::reference::CanonicalRef;
+use std::ops::RangeInclusive;
+use std::sync::LazyLock;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// A reference resolved to the sutta it falls in, with that sutta's span
+/// in each citation scheme.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Citation {
+    /// SuttaCentral uid, e.g. "dn2".
+    pub uid: String,
+    /// PTS volume and pages, e.g. "D I 47–86".
+    pub pts: Option<String>,
+    /// VRI book and paragraphs, e.g. "1.150–253".
+    pub vri: Option<String>,
+}
+
+impl Citation {
+    /// e.g. "dn2 · PTS D I 47–86 · VRI 1.150–253".
+    pub fn label(&self) -> String {
+        let mut label = self.uid.clone();
+        if let Some(pts) = &self.pts {
+            label.push_str(&format!(" · PTS {}", pts));
+        }
+        if let Some(vri) = &self.vri {
+            label.push_str(&format!(" · VRI {}", vri));
+        }
+        label
+    }
+}
+
+/// Looks a reference up in data/citations.tsv. Roman volumes ("M I 23")
+/// are read as PTS volume and page, Arabic books ("dī. ni. 1.157") as VRI
+/// book and paragraph. Where spans share a page the first sutta wins.
+pub fn resolve(reference: &CanonicalRef) -> Option<Citation> {
+    let book = reference.book.as_deref()?;
+    // First part of a locator such as "23.4"
+    let number = numeral::parse(&reference.locator)?.0[0];
+    let pts = book.chars().all(|c| matches!(c, 'I' | 'V' | 'X'));
+
+    table()
+        .iter()
+        .filter(|row| row.collection == reference.collection)
+        .find(|row| {
+            let span = if pts { &row.pts } else { &row.vri };
+            span.as_ref()
+                .is_some_and(|(b, range)| b == book && range.contains(&number))
+        })
+        .map(Row::citation)
+}
+
+// ---------------------------------------------------------
+// MAPPING TABLE
+// ---------------------------------------------------------
+
+struct Row {
+    uid: String,
+    collection: String,
+    // Book and paragraph range.
+    vri: Option<(String, RangeInclusive<u64>)>,
+    pts_siglum: String,
+    // Volume and page range.
+    pts: Option<(String, RangeInclusive<u64>)>,
+}
+
+impl Row {
+    fn citation(&self) -> Citation {
+        Citation {
+            uid: self.uid.clone(),
+            pts: self.pts.as_ref().map(|(volume, pages)| {
+                format!("{} {} {}", self.pts_siglum, volume, span_label(pages))
+            }),
+            vri: self
+                .vri
+                .as_ref()
+                .map(|(book, paras)| format!("{}.{}", book, span_label(paras))),
+        }
+    }
+}
+
+static TABLE: LazyLock<Vec<Row>> =
+    LazyLock::new(|| parse_table(include_str!("../../data/citations.tsv")));
+
+fn table() -> &'static [Row] {
+    &TABLE
+}
+
+fn span_label(range: &RangeInclusive<u64>) -> String {
+    if range.start() == range.end() {
+        range.start().to_string()
+    } else {
+        format!("{}–{}", range.start(), range.end())
+    }
+}
+
+// "12-40" or "7"; "-" for unknown.
+fn parse_range(s: &str) -> Option<RangeInclusive<u64>> {
+    let (start, end) = s.split_once('-').unwrap_or((s, s));
+    Some(start.parse().ok()?..=end.parse().ok()?)
+}
+
+// Tab-separated: uid, collection, VRI book, VRI paragraphs, PTS siglum,
+// PTS volume, PTS pages.
+fn parse_table(text: &str) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let cols: Vec<&str> = line.split('\t').map(str::trim).collect();
+        let [uid, collection, book, paras, siglum, volume, pages] = cols[..] else {
+            continue;
+        };
+        rows.push(Row {
+            uid: uid.to_string(),
+            collection: collection.to_string(),
+            vri: parse_range(paras).map(|r| (book.to_string(), r)),
+            pts_siglum: siglum.to_string(),
+            pts: parse_range(pages).map(|r| (volume.to_string(), r)),
+        });
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::reference;
+
+    fn resolve_text(text: &str) -> Option<Citation> {
+        let (_, reference) = reference::find(text).into_iter().next()?;
+        resolve(&reference)
+    }
+
+    #[test]
+    fn test_resolve_schemes() {
+        let vri = resolve_text("dī. ni. 1.157").unwrap();
+        assert_eq!(vri.uid, "dn2");
+        assert_eq!(vri.pts.as_deref(), Some("D I 47–86"));
+        assert_eq!(vri.vri.as_deref(), Some("1.150–253"));
+
+        let pts = resolve_text("(M I 23)").unwrap();
+        assert_eq!(pts.uid, "mn4");
+        assert_eq!(pts.label(), "mn4 · PTS M I 16–24");
+
+        assert_eq!(resolve_text("Dhp 5"), None);
+        assert_eq!(resolve_text("ma. ni. 3.5"), None);
+    }
+}
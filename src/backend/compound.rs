@@ -0,0 +1,244 @@
+use crate::backend::lexicon::Lexicon;
+use crate::backend::morphology;
+use crate::backend::types::{SegResult, TokenKind};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// One member of a compound. `offset` and `len` are byte positions in the
+/// token's normalized form; `headword` is the lexicon entry it matched.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompoundMember {
+    pub offset: usize,
+    pub len: usize,
+    pub headword: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompoundAnalysis {
+    pub members: Vec<CompoundMember>,
+}
+
+impl CompoundAnalysis {
+    pub fn headwords(&self) -> Vec<&str> {
+        self.members.iter().map(|m| m.headword.as_str()).collect()
+    }
+}
+
+const MAX_ANALYSES: usize = 5;
+const MAX_MEMBERS: usize = 5;
+const MIN_MEMBER_CHARS: usize = 2;
+const MAX_CANDIDATES: usize = 64;
+// Lexicon probes allowed per word, so a long word with a large lexicon
+// cannot run away.
+const MAX_STEPS: usize = 4096;
+
+/// Decompositions of a normalized word into two or more lexicon members,
+/// best first: fewer members, then longer members.
+pub fn analyze(word: &str, lexicon: &Lexicon) -> Vec<CompoundAnalysis> {
+    let mut search = Search {
+        word,
+        lexicon,
+        steps: 0,
+        dead_ends: HashSet::new(),
+        found: Vec::new(),
+    };
+    search.from(0, &mut Vec::new());
+    let mut found = search.found;
+
+    found.sort_by_key(|a| {
+        let spread: usize = a.members.iter().map(|m| m.len * m.len).sum();
+        (a.members.len(), Reverse(spread))
+    });
+    found.dedup();
+    found.truncate(MAX_ANALYSES);
+    found
+}
+
+/// Fills `compounds` on every word token.
+pub fn annotate(result: &mut SegResult, lexicon: &Lexicon) {
+    for sentence in result.segments_mut() {
+        for token in sentence.tokens.iter_mut() {
+            if token.kind == TokenKind::Word {
+                token.compounds = analyze(&token.normalized, lexicon);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------
+// SEARCH
+// ---------------------------------------------------------
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'ā' | 'i' | 'ī' | 'u' | 'ū' | 'e' | 'o')
+}
+
+fn shorten_final(word: &str) -> Option<String> {
+    let last = word.chars().last()?;
+    let short = match last {
+        'ā' => 'a',
+        'ī' => 'i',
+        'ū' => 'u',
+        _ => return None,
+    };
+    Some(format!(
+        "{}{}",
+        &word[..word.len() - last.len_utf8()],
+        short
+    ))
+}
+
+// Headwords a non-final member may stand for, allowing for a lengthened
+// final vowel or one elided before a vowel-initial member.
+fn inner_member(surface: &str, next: char, lexicon: &Lexicon) -> Vec<String> {
+    let mut out = Vec::new();
+    if lexicon.contains(surface) {
+        out.push(surface.to_string());
+    }
+    if let Some(short) = shorten_final(surface).filter(|s| lexicon.contains(s)) {
+        out.push(short);
+    }
+    if is_vowel(next) {
+        for v in ["a", "ā", "i", "u"] {
+            let restored = format!("{}{}", surface, v);
+            if lexicon.contains(&restored) {
+                out.push(restored);
+            }
+        }
+    }
+    out
+}
+
+fn final_member(surface: &str, lexicon: &Lexicon) -> Vec<String> {
+    let mut out = Vec::new();
+    if lexicon.contains(surface) {
+        out.push(surface.to_string());
+    }
+    for stem in morphology::stem_candidates(surface) {
+        if lexicon.contains(&stem) && !out.contains(&stem) {
+            out.push(stem);
+        }
+    }
+    out
+}
+
+struct Search<'w, 'l> {
+    word: &'w str,
+    lexicon: &'l Lexicon,
+    steps: usize,
+    // (offset, members so far) from which no analysis can be completed
+    dead_ends: HashSet<(usize, usize)>,
+    found: Vec<CompoundAnalysis>,
+}
+
+impl Search<'_, '_> {
+    // Extends `path` with members from `start`; returns true if any
+    // analysis was found from here.
+    fn from(&mut self, start: usize, path: &mut Vec<CompoundMember>) -> bool {
+        if self.found.len() >= MAX_CANDIDATES
+            || self.steps >= MAX_STEPS
+            || self.dead_ends.contains(&(start, path.len()))
+        {
+            return false;
+        }
+        let word = self.word;
+        let rest = &word[start..];
+        let mut any = false;
+
+        if !path.is_empty() && rest.chars().count() >= MIN_MEMBER_CHARS {
+            self.steps += 1;
+            for headword in final_member(rest, self.lexicon) {
+                let mut members = path.clone();
+                members.push(CompoundMember {
+                    offset: start,
+                    len: rest.len(),
+                    headword,
+                });
+                self.found.push(CompoundAnalysis { members });
+                any = true;
+            }
+        }
+
+        if path.len() + 1 < MAX_MEMBERS {
+            for (end, next) in rest.char_indices().skip(MIN_MEMBER_CHARS) {
+                if self.steps >= MAX_STEPS {
+                    return any;
+                }
+                self.steps += 1;
+                for headword in inner_member(&rest[..end], next, self.lexicon) {
+                    path.push(CompoundMember {
+                        offset: start,
+                        len: end,
+                        headword,
+                    });
+                    any |= self.from(start + end, path);
+                    path.pop();
+                }
+            }
+        }
+        if !any && self.steps < MAX_STEPS {
+            self.dead_ends.insert((start, path.len()));
+        }
+        any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_compounds() {
+        let lexicon = Lexicon::bundled();
+        let best = |word: &str| analyze(word, lexicon)[0].headwords().join(" + ");
+
+        assert_eq!(best("vijjācaraṇasampanno"), "vijjā + caraṇa + sampanna");
+        assert_eq!(best("purisadammasārathi"), "purisa + damma + sārathi");
+        assert_eq!(best("bhikkhusaṅghena"), "bhikkhu + saṅgha");
+        assert_eq!(best("naḷerupucimandamūle"), "naḷeru + pucimanda + mūla");
+    }
+
+    #[test]
+    fn test_elided_vowel_at_join() {
+        let lexicon = Lexicon::bundled();
+        let analysis = &analyze("abbhuggatākārena", lexicon)[0];
+
+        assert_eq!(analysis.headwords(), vec!["abbhuggata", "ākāra"]);
+        assert_eq!(analysis.members[1].offset, "abbhuggat".len());
+    }
+
+    #[test]
+    fn test_long_word_with_large_lexicon() {
+        // Every run of one to three of these syllables is a headword.
+        let syllables = ["ka", "kha", "ga", "na", "ma", "ta"];
+        let mut words: Vec<String> = syllables.iter().map(|s| s.to_string()).collect();
+        for _ in 0..2 {
+            let longer: Vec<String> = words
+                .iter()
+                .flat_map(|w| syllables.iter().map(move |s| format!("{}{}", w, s)))
+                .collect();
+            words.extend(longer);
+        }
+        words.extend(["vijjā", "caraṇa", "sampanna"].map(String::from));
+        let lexicon = Lexicon::from_text(&words.join("\n"));
+        assert!(lexicon.len() > 250);
+
+        // No member ends the word, so every split is a dead end.
+        let long = format!("{}rū", "kakhaganamata".repeat(4));
+        assert!(analyze(&long, &lexicon).is_empty());
+        let best = &analyze("vijjācaraṇasampanno", &lexicon)[0];
+        assert_eq!(best.headwords(), vec!["vijjā", "caraṇa", "sampanna"]);
+    }
+
+    #[test]
+    fn test_simple_words_are_not_split() {
+        let lexicon = Lexicon::bundled();
+        for word in ["bhagavā", "viharati", "saddhiṃ", "gotamo"] {
+            assert!(analyze(word, lexicon).is_empty(), "{}", word);
+        }
+    }
+}
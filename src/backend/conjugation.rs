@@ -0,0 +1,264 @@
+use crate::backend::morphology::{self, Number};
+use crate::backend::types::{SegResult, TokenKind};
+use std::sync::LazyLock;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VerbForm {
+    Present,
+    Aorist,
+    Future,
+    Optative,
+    Imperative,
+    Absolutive,
+    Infinitive,
+    PresentParticiple,
+    PastParticiple,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Person {
+    First,
+    Second,
+    Third,
+}
+
+impl VerbForm {
+    pub fn abbrev(self) -> &'static str {
+        match self {
+            VerbForm::Present => "pres",
+            VerbForm::Aorist => "aor",
+            VerbForm::Future => "fut",
+            VerbForm::Optative => "opt",
+            VerbForm::Imperative => "imp",
+            VerbForm::Absolutive => "abs",
+            VerbForm::Infinitive => "inf",
+            VerbForm::PresentParticiple => "ppr",
+            VerbForm::PastParticiple => "pp",
+        }
+    }
+
+    /// Forms that carry person and number and can head a clause.
+    pub fn is_finite(self) -> bool {
+        matches!(
+            self,
+            VerbForm::Present
+                | VerbForm::Aorist
+                | VerbForm::Future
+                | VerbForm::Optative
+                | VerbForm::Imperative
+        )
+    }
+
+    fn is_participle(self) -> bool {
+        matches!(self, VerbForm::PresentParticiple | VerbForm::PastParticiple)
+    }
+}
+
+impl Person {
+    pub fn abbrev(self) -> &'static str {
+        match self {
+            Person::First => "1",
+            Person::Second => "2",
+            Person::Third => "3",
+        }
+    }
+}
+
+/// One verbal reading of a word. Person and number are only set for
+/// finite forms.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerbAnalysis {
+    /// Present stem, or participle base, e.g. "vihara" or "pabbaj".
+    pub stem: String,
+    pub ending: String,
+    pub form: VerbForm,
+    pub person: Option<Person>,
+    pub number: Option<Number>,
+}
+
+impl VerbAnalysis {
+    /// Short gloss such as "vihara pres 3 sg".
+    pub fn label(&self) -> String {
+        let mut label = format!("{} {}", self.stem, self.form.abbrev());
+        if let (Some(person), Some(number)) = (self.person, self.number) {
+            label.push_str(&format!(" {} {}", person.abbrev(), number.abbrev()));
+        }
+        label
+    }
+}
+
+/// Possible verbal analyses of a normalized word, finite forms first.
+pub fn analyze(word: &str) -> Vec<VerbAnalysis> {
+    let mut out = Vec::new();
+    for row in table() {
+        if row.form.is_participle() {
+            // pabbajito → pabbajita → pabbaj + ita
+            for nominal in morphology::stem_candidates(word) {
+                if let Some(stem) = stem_before(&nominal, row) {
+                    out.push(row.reading(stem));
+                }
+            }
+        } else if let Some(stem) = stem_before(word, row) {
+            out.push(row.reading(stem));
+        }
+    }
+    out.dedup();
+    out.sort_by_key(|a| !a.form.is_finite());
+    out
+}
+
+/// Fills `verbs` on word tokens and on their sandhi parts.
+pub fn annotate(result: &mut SegResult) {
+    for sentence in result.segments_mut() {
+        for token in sentence.tokens.iter_mut() {
+            if token.kind != TokenKind::Word {
+                continue;
+            }
+            token.verbs = analyze(&token.normalized);
+            for sub in token.sub_tokens.iter_mut() {
+                sub.verbs = analyze(&sub.normalized);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------
+// CONJUGATION TABLE
+// ---------------------------------------------------------
+
+const MIN_STEM_CHARS: usize = 2;
+
+struct Conjugation {
+    ending: String,
+    // Letters allowed right before the ending; empty means any.
+    after: Vec<char>,
+    form: VerbForm,
+    person: Option<Person>,
+    number: Option<Number>,
+}
+
+impl Conjugation {
+    fn reading(&self, stem: String) -> VerbAnalysis {
+        VerbAnalysis {
+            stem,
+            ending: self.ending.clone(),
+            form: self.form,
+            person: self.person,
+            number: self.number,
+        }
+    }
+}
+
+static TABLE: LazyLock<Vec<Conjugation>> =
+    LazyLock::new(|| parse_table(include_str!("../../data/conjugations.tsv")));
+
+fn table() -> &'static [Conjugation] {
+    &TABLE
+}
+
+fn stem_before(word: &str, row: &Conjugation) -> Option<String> {
+    let stem = word.strip_suffix(row.ending.as_str())?;
+    let last = stem.chars().last()?;
+    let allowed = row.after.is_empty() || row.after.contains(&last);
+    (allowed && stem.chars().count() >= MIN_STEM_CHARS).then(|| stem.to_string())
+}
+
+// Tab-separated: ending, preceding letters, form, person, number.
+fn parse_table(text: &str) -> Vec<Conjugation> {
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let cols: Vec<&str> = line.split('\t').collect();
+        let [ending, after, form, person, number] = cols[..] else {
+            continue;
+        };
+        let Some(form) = parse_form(form) else {
+            continue;
+        };
+        rows.push(Conjugation {
+            ending: ending.to_string(),
+            after: after
+                .split('/')
+                .filter(|s| *s != "*")
+                .filter_map(|s| s.chars().next())
+                .collect(),
+            form,
+            person: [Person::First, Person::Second, Person::Third]
+                .into_iter()
+                .find(|p| p.abbrev() == person),
+            number: [Number::Singular, Number::Plural]
+                .into_iter()
+                .find(|n| n.abbrev() == number),
+        });
+    }
+    rows
+}
+
+fn parse_form(s: &str) -> Option<VerbForm> {
+    [
+        VerbForm::Present,
+        VerbForm::Aorist,
+        VerbForm::Future,
+        VerbForm::Optative,
+        VerbForm::Imperative,
+        VerbForm::Absolutive,
+        VerbForm::Infinitive,
+        VerbForm::PresentParticiple,
+        VerbForm::PastParticiple,
+    ]
+    .into_iter()
+    .find(|f| f.abbrev() == s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::{SegOptions, process_text_with};
+
+    fn best(word: &str) -> String {
+        analyze(word).first().map(|a| a.label()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_sample_verbs() {
+        assert_eq!(best("viharati"), "vihara pres 3 sg");
+        assert_eq!(best("pavedeti"), "pavede pres 3 sg");
+        assert_eq!(best("deseti"), "dese pres 3 sg");
+        assert_eq!(best("pakāseti"), "pakāse pres 3 sg");
+        assert_eq!(best("sacchikatvā"), "sacchika abs");
+        assert!(
+            analyze("pabbajito")
+                .iter()
+                .any(|a| a.form == VerbForm::PastParticiple && a.stem == "pabbaj")
+        );
+        for word in ["kitti", "iti", "bhagavā", "kho"] {
+            assert!(analyze(word).is_empty(), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_main_verb() {
+        let text = "So imaṃ lokaṃ sayaṃ abhiññā sacchikatvā pavedeti. \
+                    Sādhu kho pana arahataṃ dassanaṃ hotī’’’ti.";
+        let options = SegOptions {
+            sandhi: true,
+            ..Default::default()
+        };
+        let mut result = process_text_with(text, &options).to_owned_data();
+        annotate(&mut result);
+
+        let verbs: Vec<&str> = result
+            .sentences
+            .iter()
+            .filter_map(|s| s.main_verb())
+            .map(|t| t.text.as_ref())
+            .collect();
+        assert_eq!(verbs, vec!["pavedeti", "hotī’’’ti"]);
+    }
+}
@@ -0,0 +1,240 @@
+use crate::backend::types::{SegResult, Token, TokenKind};
+use std::ops::Range;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Text standing in for a peyyāla marker, copied from an earlier
+/// occurrence of the same formula.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expansion {
+    /// Id of the `TokenKind::Elision` token this fills.
+    pub token_id: usize,
+    pub text: String,
+    /// Offsets of the copied passage in the document.
+    pub source: Range<usize>,
+}
+
+/// True if `text` ends with a peyyāla marker, so a sentence break right
+/// after it is not a real one.
+pub fn ends_with_marker(text: &str) -> bool {
+    let text = text.trim_end();
+    MARKERS.iter().any(|m| text.ends_with(m))
+}
+
+/// Joins `…` `pe` `…`, `.` `.` `.` `la` `.` `.` `.` and similar runs into
+/// single `TokenKind::Elision` tokens. A bare "pe" or "ฯลฯ" also counts.
+/// `text` is the sentence the tokens were cut from, starting at `base_offset`.
+pub fn join_markers<'a>(
+    tokens: Vec<Token<'a>>,
+    text: &'a str,
+    base_offset: usize,
+) -> Vec<Token<'a>> {
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let Some(end) = marker_run(&tokens, i) else {
+            out.push(tokens[i].clone());
+            i += 1;
+            continue;
+        };
+        out.push(Token::spanning(
+            &tokens[i],
+            &tokens[end],
+            text,
+            base_offset,
+            TokenKind::Elision,
+        ));
+        i = end + 1;
+    }
+    out
+}
+
+/// Fills `expansions` for each elision whose surrounding words occur
+/// earlier in the document: the words just before the marker locate the
+/// formula, the word just after it ends the copied gap.
+pub fn expand(result: &mut SegResult) {
+    let tokens: Vec<&Token> = result
+        .segments()
+        .into_iter()
+        .flat_map(|s| &s.tokens)
+        .collect();
+    // Indices of the tokens used for matching.
+    let content: Vec<usize> = (0..tokens.len())
+        .filter(|&i| is_content(tokens[i]) || tokens[i].kind == TokenKind::Elision)
+        .collect();
+
+    let mut expansions = Vec::new();
+    for (c, &i) in content.iter().enumerate() {
+        if tokens[i].kind != TokenKind::Elision {
+            continue;
+        }
+        let Some(&after) = content.get(c + 1) else {
+            continue;
+        };
+        if let Some(gap) = find_gap(&tokens, &content, c, after) {
+            expansions.push(Expansion {
+                token_id: tokens[i].id,
+                text: spaced_text(&tokens[gap.clone()]),
+                source: tokens[gap.start].offset..end_offset(tokens[gap.end - 1]),
+            });
+        }
+    }
+    result.expansions = expansions;
+}
+
+// ---------------------------------------------------------
+// MARKERS
+// ---------------------------------------------------------
+
+const MARKERS: [&str; 7] = [
+    "…pe…",
+    "...pe...",
+    "…la…",
+    "...la...",
+    "…pa…",
+    "...pa...",
+    "ฯลฯ",
+];
+
+// Marker words; only "pe" is recognised without surrounding dots.
+const MARKER_WORDS: [&str; 3] = ["pe", "la", "pa"];
+const BARE_MARKER: &str = "pe";
+const THAI_MARKER: &str = "ฯลฯ";
+
+// Words either side of the marker used to find the formula.
+const MAX_CONTEXT: usize = 2;
+const MAX_GAP_TOKENS: usize = 200;
+
+fn is_ellipsis(token: &Token) -> bool {
+    token.kind == TokenKind::Punctuation && token.text.chars().all(|c| matches!(c, '…' | '.'))
+}
+
+fn is_content(token: &Token) -> bool {
+    matches!(
+        token.kind,
+        TokenKind::Word | TokenKind::Particle | TokenKind::Number
+    )
+}
+
+fn end_offset(token: &Token) -> usize {
+    token.offset + token.text.len()
+}
+
+// Dots, a marker word, dots, with no gaps between; or ฯลฯ.
+fn marker_run(tokens: &[Token], start: usize) -> Option<usize> {
+    if tokens[start].normalized == THAI_MARKER {
+        return Some(start);
+    }
+    let adjacent = |a: &Token, b: &Token| end_offset(a) == b.offset;
+
+    let mut word = start;
+    while word < tokens.len()
+        && is_ellipsis(&tokens[word])
+        && (word == start || adjacent(&tokens[word - 1], &tokens[word]))
+    {
+        word += 1;
+    }
+    let marker = tokens.get(word)?;
+    let leading = word > start;
+    if marker.kind != TokenKind::Word
+        || !MARKER_WORDS.contains(&marker.normalized.as_ref())
+        || (leading && !adjacent(&tokens[word - 1], marker))
+    {
+        return None;
+    }
+
+    let mut end = word;
+    while let Some(next) = tokens.get(end + 1) {
+        if !is_ellipsis(next) || !adjacent(&tokens[end], next) {
+            break;
+        }
+        end += 1;
+    }
+    let dotted = leading || end > word;
+    (dotted || marker.normalized == BARE_MARKER).then_some(end)
+}
+
+// ---------------------------------------------------------
+// EXPANSION
+// ---------------------------------------------------------
+
+// Token range to copy for the elision at `content[c]`, ending before the
+// earlier occurrence of the token at `after`.
+fn find_gap(tokens: &[&Token], content: &[usize], c: usize, after: usize) -> Option<Range<usize>> {
+    let same = |a: usize, b: usize| tokens[a].normalized == tokens[b].normalized;
+    let before = &content[c.saturating_sub(MAX_CONTEXT)..c];
+    let before = &before[before
+        .iter()
+        .rposition(|&i| tokens[i].kind == TokenKind::Elision)
+        .map_or(0, |p| p + 1)..];
+
+    // Longest context first, nearest occurrence first.
+    for len in (1..=before.len()).rev() {
+        let context = &before[before.len() - len..];
+        for start in (0..c.saturating_sub(len + 1)).rev() {
+            let window = &content[start..start + len];
+            if !window.iter().zip(context).all(|(&a, &b)| same(a, b)) {
+                continue;
+            }
+            let from = content[start + len - 1] + 1;
+            let limit = content[c - len];
+            let to = (from..limit.min(from + MAX_GAP_TOKENS))
+                .find(|&i| is_content(tokens[i]) && same(i, after));
+            if let Some(to) = to.filter(|&to| to > from) {
+                return Some(from..to);
+            }
+        }
+    }
+    None
+}
+
+// Token texts with a space wherever the source had one.
+fn spaced_text(tokens: &[&Token]) -> String {
+    let mut out = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 && tokens[i - 1].offset + tokens[i - 1].text.len() < token.offset {
+            out.push(' ');
+        }
+        out.push_str(&token.text);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::{SegOptions, process_text, process_text_with};
+
+    #[test]
+    fn test_markers_do_not_end_sentences() {
+        let text = "Bhikkhu viharati ...pe... Tena samayena …la… bhagavā ฯลฯ pe viharati.";
+        let result = process_text(text);
+        assert_eq!(result.sentences.len(), 1);
+
+        let elisions: Vec<&str> = result.sentences[0]
+            .tokens
+            .iter()
+            .filter(|t| t.kind == TokenKind::Elision)
+            .map(|t| t.text.as_ref())
+            .collect();
+        assert_eq!(elisions, vec!["...pe...", "…la…", "ฯลฯ", "pe"]);
+    }
+
+    #[test]
+    fn test_expansion_from_earlier_formula() {
+        let text = "Kāye kāyānupassī viharati ātāpī sampajāno satimā. \
+                    Vedanāsu vedanānupassī viharati …pe… satimā.";
+        let options = SegOptions {
+            expand_elisions: true,
+            ..Default::default()
+        };
+        let result = process_text_with(text, &options);
+
+        assert_eq!(result.expansions.len(), 1);
+        let expansion = &result.expansions[0];
+        assert_eq!(expansion.text, "ātāpī sampajāno");
+        assert_eq!(&text[expansion.source.clone()], "ātāpī sampajāno");
+    }
+}
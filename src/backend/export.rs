@@ -0,0 +1,85 @@
+use crate::backend::types::SegResult;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+pub const TSV_HEADER: &str =
+    "sentence\tid\toffset\tkind\ttext\tnormalized\tkey\tascii\tvelthuis\tharvard_kyoto";
+
+/// One row per token. The ASCII and Velthuis/Harvard-Kyoto columns let
+/// exports be grepped from a plain keyboard.
+pub fn to_tsv(result: &SegResult) -> String {
+    let mut out = String::from(TSV_HEADER);
+    out.push('\n');
+    for (i, sentence) in result.segments().into_iter().enumerate() {
+        for token in &sentence.tokens {
+            let _ = writeln!(
+                out,
+                "{}\t{}\t{}\t{:?}\t{}\t{}\t{}\t{}\t{}\t{}",
+                i + 1,
+                token.id,
+                token.offset,
+                token.kind,
+                field(&token.text),
+                field(&token.normalized),
+                field(&token.key),
+                field(&token.ascii_key()),
+                field(&token.velthuis()),
+                field(&token.harvard_kyoto()),
+            );
+        }
+    }
+    out
+}
+
+/// Wraps text in a `data:` URI so the browser can offer it as a download.
+pub fn to_data_uri(mime: &str, text: &str) -> String {
+    let mut out = format!("data:{};charset=utf-8,", mime);
+    for b in text.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            let _ = write!(out, "%{:02X}", b);
+        }
+    }
+    out
+}
+
+// Tabs and newlines would break the row structure.
+fn field(text: &str) -> Cow<'_, str> {
+    if text.contains(['\t', '\n', '\r']) {
+        Cow::Owned(text.replace(['\t', '\n', '\r'], " "))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    #[test]
+    fn test_tsv_rows() {
+        let tsv = to_tsv(&process_text("Bhagavā viharati. Saṅgho."));
+        let rows: Vec<Vec<&str>> = tsv.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(rows[0].join("\t"), TSV_HEADER);
+        assert!(rows.iter().all(|r| r.len() == rows[0].len()));
+        assert_eq!(rows[1][..5], ["1", "1", "0", "Word", "Bhagavā"]);
+        assert_eq!(rows[1][7], "bhagava");
+        assert!(rows.iter().any(|r| r[0] == "2" && r[4] == "Saṅgho"));
+    }
+
+    #[test]
+    fn test_field_and_data_uri() {
+        assert_eq!(field("a\tb\nc\r"), "a b c ");
+        assert!(matches!(field("bhagavā"), Cow::Borrowed(_)));
+        assert_eq!(
+            to_data_uri("text/plain", "ā b,%"),
+            "data:text/plain;charset=utf-8,%C4%81%20b%2C%25"
+        );
+    }
+}
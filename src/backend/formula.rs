@@ -0,0 +1,161 @@
+use crate::backend::types::{SegResult, Token, TokenKind};
+use std::collections::HashMap;
+use std::ops::Range;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Passages shorter than this many words are not reported by `annotate`.
+pub const DEFAULT_MIN_WORDS: usize = 6;
+
+/// One place a repeated passage occurs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassageSpan {
+    /// Index of the document in the slice given to `find_repeats`.
+    pub document: usize,
+    /// Byte offsets of the passage in that document.
+    pub range: Range<usize>,
+    /// Ids of the tokens in the passage.
+    pub tokens: Range<usize>,
+}
+
+/// A word sequence found at least twice, extended as far as all its
+/// occurrences agree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepeatedPassage {
+    /// Normalized words of the passage.
+    pub words: Vec<String>,
+    pub occurrences: Vec<PassageSpan>,
+}
+
+/// Maximal repeated passages of at least `min_words` words, longest first.
+/// Punctuation is ignored, so passages may span sentence breaks, and words
+/// are compared by spelling key, so editions with different spellings align.
+pub fn find_repeats(documents: &[&SegResult], min_words: usize) -> Vec<RepeatedPassage> {
+    let streams: Vec<Vec<&Token>> = documents.iter().map(|d| words(d)).collect();
+    let min_words = min_words.max(1);
+
+    // Positions of every repeated seed n-gram.
+    let mut seeds: HashMap<Vec<&str>, Vec<(usize, usize)>> = HashMap::new();
+    for (doc, stream) in streams.iter().enumerate() {
+        for start in 0..stream.len().saturating_sub(min_words - 1) {
+            let key = stream[start..start + min_words]
+                .iter()
+                .map(|t| t.key.as_ref())
+                .collect();
+            seeds.entry(key).or_default().push((doc, start));
+        }
+    }
+
+    let word_at = |(doc, i): (usize, usize)| streams[doc].get(i).map(|t| t.key.as_ref());
+    let mut passages = Vec::new();
+    for positions in seeds.into_values().filter(|p| p.len() > 1) {
+        // Left-maximal only: otherwise the seed one word earlier covers it.
+        let extends_left = positions.iter().all(|&(_, i)| i > 0)
+            && positions.iter().all(|&(doc, i)| {
+                word_at((doc, i - 1)) == word_at((positions[0].0, positions[0].1 - 1))
+            });
+        if extends_left {
+            continue;
+        }
+
+        let mut len = min_words;
+        while positions.iter().all(|&(doc, i)| {
+            let next = word_at((doc, i + len));
+            next.is_some() && next == word_at((positions[0].0, positions[0].1 + len))
+        }) {
+            len += 1;
+        }
+
+        let (doc, first) = positions[0];
+        passages.push(RepeatedPassage {
+            words: streams[doc][first..first + len]
+                .iter()
+                .map(|t| t.normalized.to_string())
+                .collect(),
+            occurrences: positions
+                .iter()
+                .map(|&(doc, i)| {
+                    let (start, end) = (streams[doc][i], streams[doc][i + len - 1]);
+                    PassageSpan {
+                        document: doc,
+                        range: start.offset..end.offset + end.text.len(),
+                        tokens: start.id..end.id + 1,
+                    }
+                })
+                .collect(),
+        });
+    }
+
+    passages.sort_by(|a, b| {
+        b.words.len().cmp(&a.words.len()).then_with(|| {
+            a.occurrences[0]
+                .range
+                .start
+                .cmp(&b.occurrences[0].range.start)
+        })
+    });
+    passages
+}
+
+/// Fills `formulas` with the passages repeated within `result`.
+pub fn annotate(result: &mut SegResult) {
+    result.formulas = find_repeats(&[result], DEFAULT_MIN_WORDS);
+}
+
+// Tokens that take part in matching.
+fn words<'r, 'a>(result: &'r SegResult<'a>) -> Vec<&'r Token<'a>> {
+    result
+        .segments()
+        .into_iter()
+        .flat_map(|s| &s.tokens)
+        .filter(|t| {
+            matches!(
+                t.kind,
+                TokenKind::Word | TokenKind::Particle | TokenKind::Number
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    #[test]
+    fn test_repeated_passage_in_one_document() {
+        let text = "Tena samayena buddho bhagavā verañjāyaṃ viharati naḷerupucimandamūle mahatā \
+                    bhikkhusaṅghena saddhiṃ. Assosi kho verañjo brāhmaṇo – samaṇo khalu, bho, \
+                    gotamo verañjāyaṃ viharati naḷerupucimandamūle mahatā bhikkhusaṅghena saddhiṃ.";
+        let result = process_text(text);
+        let repeats = find_repeats(&[&result], 4);
+
+        assert_eq!(repeats.len(), 1);
+        assert_eq!(
+            repeats[0].words.join(" "),
+            "verañjāyaṃ viharati naḷerupucimandamūle mahatā bhikkhusaṅghena saddhiṃ"
+        );
+        let spans: Vec<&str> = repeats[0]
+            .occurrences
+            .iter()
+            .map(|o| &text[o.range.clone()])
+            .collect();
+        assert_eq!(spans[0], spans[1]);
+        assert!(spans[0].starts_with("verañjāyaṃ"));
+    }
+
+    #[test]
+    fn test_repeats_across_documents() {
+        let a = process_text("itipi so bhagavā arahaṃ sammāsambuddho vijjācaraṇasampanno.");
+        let b = process_text("Evaṃ kho, itipi so bhagavā arahaṃ sammāsambuddho, ti.");
+        let repeats = find_repeats(&[&a, &b], 3);
+
+        assert_eq!(repeats.len(), 1);
+        assert_eq!(repeats[0].words.len(), 5);
+        let documents: Vec<usize> = repeats[0].occurrences.iter().map(|o| o.document).collect();
+        assert_eq!(documents, vec![0, 1]);
+        assert!(find_repeats(&[&a, &b], 6).is_empty());
+    }
+}
@@ -0,0 +1,79 @@
+use crate::backend::conjugation;
+use crate::backend::lexicon::Lexicon;
+use crate::backend::morphology;
+use crate::backend::types::{SegResult, TokenKind};
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Headwords a normalized word may belong to, most likely first:
+/// irregular forms listed in the lexicon, the word itself, attested
+/// nominal stems, then verbs by their 3rd singular present.
+pub fn lemmatize(word: &str, lexicon: &Lexicon) -> Vec<String> {
+    let mut out: Vec<String> = lexicon.headwords_for_form(word).to_vec();
+    if lexicon.contains(word) {
+        out.push(word.to_string());
+    }
+    for reading in morphology::analyze(word, lexicon) {
+        if reading.attested {
+            out.push(reading.stem);
+        }
+    }
+    for reading in conjugation::analyze(word) {
+        let citation = format!("{}ti", reading.stem);
+        if reading.form.is_finite() && lexicon.contains(&citation) {
+            out.push(citation);
+        }
+    }
+
+    let mut seen = Vec::with_capacity(out.len());
+    out.retain(|lemma| {
+        let first = !seen.contains(lemma);
+        seen.push(lemma.clone());
+        first
+    });
+    out
+}
+
+/// Sets `lemma` on word tokens and their sandhi parts.
+pub fn annotate(result: &mut SegResult, lexicon: &Lexicon) {
+    for sentence in result.segments_mut() {
+        for token in sentence.tokens.iter_mut() {
+            if token.kind != TokenKind::Word {
+                continue;
+            }
+            token.lemma = lemmatize(&token.normalized, lexicon).into_iter().next();
+            for sub in token.sub_tokens.iter_mut() {
+                sub.lemma = lemmatize(&sub.normalized, lexicon).into_iter().next();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    #[test]
+    fn test_lemmatize() {
+        let lexicon = Lexicon::bundled();
+        let best = |word: &str| lemmatize(word, lexicon).into_iter().next();
+
+        assert_eq!(best("bhagavato").as_deref(), Some("bhagavant"));
+        assert_eq!(best("bhikkhusatehi").as_deref(), Some("bhikkhusata"));
+        assert_eq!(best("deseti").as_deref(), Some("deseti"));
+        assert_eq!(best("assosi").as_deref(), Some("suṇāti"));
+        assert_eq!(best("xyzzy"), None);
+    }
+
+    #[test]
+    fn test_lemma_frequencies() {
+        let mut result = process_text("bhagavā bhagavantaṃ bhagavato ca.").to_owned_data();
+        annotate(&mut result, Lexicon::bundled());
+
+        let counts = result.lemma_frequencies();
+        assert_eq!(counts[0], ("bhagavant", 3));
+    }
+}
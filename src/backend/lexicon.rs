@@ -0,0 +1,85 @@
+use crate::backend::normalize::normalize_pali;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+static BUNDLED: LazyLock<Lexicon> =
+    LazyLock::new(|| Lexicon::from_text(include_str!("../../data/headwords.txt")));
+
+/// A set of headwords (stems), plus irregular forms mapped to their
+/// headwords, all compared in normalized form.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lexicon {
+    headwords: HashSet<String>,
+    forms: HashMap<String, Vec<String>>,
+}
+
+impl Lexicon {
+    /// One headword per line, or `form<TAB>headword`; blank lines and `#`
+    /// comments are skipped.
+    pub fn from_text(text: &str) -> Self {
+        let mut lexicon = Self::default();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.split_once('\t') {
+                Some((form, headword)) => {
+                    let headword = normalize_pali(headword.trim()).into_owned();
+                    lexicon
+                        .forms
+                        .entry(normalize_pali(form.trim()).into_owned())
+                        .or_default()
+                        .push(headword.clone());
+                    lexicon.headwords.insert(headword);
+                }
+                None => {
+                    lexicon.headwords.insert(normalize_pali(line).into_owned());
+                }
+            }
+        }
+        lexicon
+    }
+
+    /// The headword list shipped in `data/headwords.txt`.
+    pub fn bundled() -> &'static Lexicon {
+        &BUNDLED
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.headwords.contains(word)
+    }
+
+    /// Headwords listed for an irregular form.
+    pub fn headwords_for_form(&self, form: &str) -> &[String] {
+        self.forms.get(form).map_or(&[], |h| h.as_slice())
+    }
+
+    pub fn len(&self) -> usize {
+        self.headwords.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headwords.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_text() {
+        let lexicon =
+            Lexicon::from_text("# stems\nsaṁgha\n\nbuddha  # the Buddha\nassosi\tsuṇāti\n");
+        assert_eq!(lexicon.len(), 3);
+        assert_eq!(lexicon.headwords_for_form("assosi"), ["suṇāti"]);
+        assert!(lexicon.contains("saṃgha"));
+        assert!(lexicon.contains("buddha"));
+        assert!(!Lexicon::bundled().is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+use crate::backend::numeral;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Counting systems a list label can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Series {
+    /// 1, ၁, १, ๑ …
+    Digits,
+    /// i, ii, iv … in either case.
+    Roman,
+    /// ka, kha, ga … or က, ခ, ဂ … in Pali alphabet order.
+    Consonants,
+    /// a, b, c …
+    Latin,
+}
+
+/// Punctuation around a list label.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Enclosure {
+    /// "(1)"
+    Parens,
+    /// "1)"
+    CloseParen,
+    /// "1."
+    Dot,
+    /// "၁။", "၁၊"
+    MyanmarSection,
+}
+
+/// Which list markers the segmenter accepts. Series are tried in order,
+/// so a label readable in two ("i") takes the first. Letter series need
+/// a parenthesis, since "a." or "ka." are too often abbreviations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListGrammar {
+    pub series: Vec<Series>,
+    pub enclosures: Vec<Enclosure>,
+    pub max_label_chars: usize,
+}
+
+impl Default for ListGrammar {
+    fn default() -> Self {
+        Self {
+            series: vec![
+                Series::Digits,
+                Series::Roman,
+                Series::Latin,
+                Series::Consonants,
+            ],
+            enclosures: vec![
+                Enclosure::Parens,
+                Enclosure::CloseParen,
+                Enclosure::Dot,
+                Enclosure::MyanmarSection,
+            ],
+            max_label_chars: 4,
+        }
+    }
+}
+
+/// A parsed list marker such as "(ka)", with its position in the series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListMarker {
+    /// Marker as written, e.g. "(ka)".
+    pub text: String,
+    /// Label inside the punctuation, e.g. "ka".
+    pub label: String,
+    pub series: Series,
+    pub enclosure: Enclosure,
+    /// 1-based position: "(ka)" and "ii." are 1 and 2.
+    pub number: Option<u64>,
+}
+
+impl ListGrammar {
+    /// Parses `text`, trimmed, as one whole list marker.
+    pub fn parse(&self, text: &str) -> Option<ListMarker> {
+        let text = text.trim();
+        let (label, enclosure) = split_enclosure(text)?;
+        if !self.enclosures.contains(&enclosure)
+            || label.is_empty()
+            || label.chars().count() > self.max_label_chars
+        {
+            return None;
+        }
+        self.series.iter().find_map(|&series| {
+            let parenthesized = matches!(enclosure, Enclosure::Parens | Enclosure::CloseParen);
+            if is_letter_series(series) && !parenthesized {
+                return None;
+            }
+            let number = series_number(series, label)?;
+            Some(ListMarker {
+                text: text.to_string(),
+                label: label.to_string(),
+                series,
+                enclosure,
+                number: Some(number),
+            })
+        })
+    }
+
+    /// The list marker opening a sentence, if any.
+    pub fn leading(&self, text: &str) -> Option<ListMarker> {
+        let text = text.trim_start();
+        let end = if text.starts_with('(') {
+            text.find(')')? + 1
+        } else {
+            text.find(char::is_whitespace)?
+        };
+        let rest = &text[end..];
+        if !rest.starts_with(char::is_whitespace) && !rest.is_empty() {
+            return None;
+        }
+        self.parse(&text[..end])
+    }
+}
+
+// ---------------------------------------------------------
+// SERIES
+// ---------------------------------------------------------
+
+const PALI_CONSONANTS: [&str; 33] = [
+    "ka", "kha", "ga", "gha", "ṅa", "ca", "cha", "ja", "jha", "ña", "ṭa", "ṭha", "ḍa", "ḍha", "ṇa",
+    "ta", "tha", "da", "dha", "na", "pa", "pha", "ba", "bha", "ma", "ya", "ra", "la", "va", "sa",
+    "ha", "ḷa", "a",
+];
+
+const MYANMAR_CONSONANTS: [&str; 33] = [
+    "က", "ခ", "ဂ", "ဃ", "င", "စ", "ဆ", "ဇ", "ဈ", "ည", "ဋ", "ဌ", "ဍ", "ဎ", "ဏ", "တ", "ထ", "ဒ", "ဓ",
+    "န", "ပ", "ဖ", "ဗ", "ဘ", "မ", "ယ", "ရ", "လ", "ဝ", "သ", "ဟ", "ဠ", "အ",
+];
+
+fn is_letter_series(series: Series) -> bool {
+    matches!(series, Series::Consonants | Series::Latin)
+}
+
+fn series_number(series: Series, label: &str) -> Option<u64> {
+    let position = |list: &[&str]| list.iter().position(|&l| l == label).map(|i| i as u64 + 1);
+    match series {
+        Series::Digits => numeral::parse(label)?.as_integer(),
+        Series::Roman => numeral::roman_value(label),
+        Series::Consonants => position(&PALI_CONSONANTS).or_else(|| position(&MYANMAR_CONSONANTS)),
+        Series::Latin => {
+            let mut chars = label.chars();
+            let c = chars.next().filter(char::is_ascii_lowercase)?;
+            chars.next().is_none().then(|| (c as u8 - b'a') as u64 + 1)
+        }
+    }
+}
+
+fn split_enclosure(text: &str) -> Option<(&str, Enclosure)> {
+    if let Some(inner) = text.strip_prefix('(') {
+        return Some((inner.strip_suffix(')')?, Enclosure::Parens));
+    }
+    if let Some(label) = text.strip_suffix(')') {
+        return Some((label, Enclosure::CloseParen));
+    }
+    if let Some(label) = text.strip_suffix('.') {
+        return Some((label, Enclosure::Dot));
+    }
+    let label = text.strip_suffix('။').or_else(|| text.strip_suffix('၊'))?;
+    Some((label, Enclosure::MyanmarSection))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+    use crate::backend::types::TokenKind;
+
+    #[test]
+    fn test_marker_forms() {
+        let grammar = ListGrammar::default();
+        let read = |text: &str| grammar.parse(text).map(|m| (m.series, m.number.unwrap()));
+
+        assert_eq!(read("(ka)"), Some((Series::Consonants, 1)));
+        assert_eq!(read("(kha)"), Some((Series::Consonants, 2)));
+        assert_eq!(read("(i)"), Some((Series::Roman, 1)));
+        assert_eq!(read("ii."), Some((Series::Roman, 2)));
+        assert_eq!(read("(၁)"), Some((Series::Digits, 1)));
+        assert_eq!(read("၁၂။"), Some((Series::Digits, 12)));
+        assert_eq!(read("a)"), Some((Series::Latin, 1)));
+        assert_eq!(read("(b)"), Some((Series::Latin, 2)));
+        assert_eq!(read("(က)"), Some((Series::Consonants, 1)));
+        assert_eq!(read("(1)"), Some((Series::Digits, 1)));
+        for text in ["ka.", "a.", "(bhagavā)", "ni.", "1"] {
+            assert_eq!(read(text), None, "{}", text);
+        }
+
+        let digits_only = ListGrammar {
+            series: vec![Series::Digits],
+            ..Default::default()
+        };
+        assert!(digits_only.parse("(ka)").is_none());
+    }
+
+    #[test]
+    fn test_sentence_markers() {
+        let text = "(ka) Rūpaṃ aniccaṃ.\n\n(kha) Vedanā aniccā.\n\nii. Saññā aniccā.";
+        let result = process_text(text);
+        let markers: Vec<(String, u64)> = result
+            .sentences
+            .iter()
+            .filter_map(|s| s.list_marker.as_ref())
+            .map(|m| (m.label.clone(), m.number.unwrap()))
+            .collect();
+        assert_eq!(
+            markers,
+            vec![
+                ("ka".to_string(), 1),
+                ("kha".to_string(), 2),
+                ("ii".to_string(), 2)
+            ]
+        );
+        assert_eq!(result.sentences[2].text.trim(), "ii. Saññā aniccā.");
+
+        // A paragraph number is a Para marker only.
+        let result = process_text("1. Tena samayena buddho bhagavā verañjāyaṃ viharati.");
+        let sentence = &result.sentences[0];
+        assert_eq!(sentence.tokens[0].kind, TokenKind::ParaMarker);
+        assert_eq!(sentence.list_marker, None);
+    }
+}
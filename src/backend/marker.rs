@@ -0,0 +1,249 @@
+use crate::backend::numeral::{self, dotted_number};
+use crate::backend::types::{Token, TokenKind};
+use std::ops::Range;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocatorKind {
+    Page,
+    Para,
+}
+
+/// A page or paragraph number of some edition, found in the text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Locator {
+    /// Document offset of the marker.
+    pub offset: usize,
+    pub kind: LocatorKind,
+    /// Edition or label as written ("PTS", "M", "page"); `None` for a bare
+    /// VRI paragraph number.
+    pub edition: Option<String>,
+    pub value: String,
+}
+
+impl Locator {
+    /// e.g. "PTS 1.2", "M 1.5" or "¶ 12".
+    pub fn label(&self) -> String {
+        match &self.edition {
+            Some(edition) => format!("{} {}", edition, self.value),
+            None => format!("¶ {}", self.value),
+        }
+    }
+
+    pub fn token_kind(&self) -> TokenKind {
+        match self.kind {
+            LocatorKind::Page => TokenKind::PageMarker,
+            LocatorKind::Para => TokenKind::ParaMarker,
+        }
+    }
+}
+
+/// Edition markers in `text`: "[PTS 1.2]", "[page 023]", "[M.0001]",
+/// bare "M.1.5", and, if `text` opens a paragraph, a leading VRI paragraph
+/// number "1.". Offsets are relative to `text`.
+pub fn find(text: &str, paragraph_start: bool) -> Vec<(Range<usize>, Locator)> {
+    let mut out = Vec::new();
+    if paragraph_start {
+        let start = text.len() - text.trim_start().len();
+        if let Some(len) = dotted_number(&text[start..])
+            && text[start + len..].starts_with('.')
+            && text[start + len + 1..].starts_with(char::is_whitespace)
+        {
+            let locator = Locator {
+                offset: start,
+                kind: LocatorKind::Para,
+                edition: None,
+                value: text[start..start + len].to_string(),
+            };
+            out.push((start..start + len + 1, locator));
+        }
+    }
+
+    let mut prev: Option<char> = None;
+    let mut skip_to = 0;
+    for (i, c) in text.char_indices() {
+        let at_boundary = !prev.is_some_and(char::is_alphanumeric);
+        prev = Some(c);
+        if i < skip_to || !at_boundary {
+            continue;
+        }
+        let found = if c == '[' {
+            bracketed(&text[i..])
+        } else {
+            bare(&text[i..])
+        };
+        if let Some((len, kind, edition, value)) = found {
+            let locator = Locator {
+                offset: i,
+                kind,
+                edition: Some(edition.to_string()),
+                value: value.to_string(),
+            };
+            out.push((i..i + len, locator));
+            skip_to = i + len;
+        }
+    }
+    out
+}
+
+/// Turns the tokens of each marker into one `PageMarker` or `ParaMarker`
+/// token and returns the sentence's locators with document offsets.
+/// `text` is the sentence the tokens were cut from, starting at `base_offset`.
+pub fn join<'a>(
+    tokens: Vec<Token<'a>>,
+    text: &'a str,
+    base_offset: usize,
+    paragraph_start: bool,
+) -> (Vec<Token<'a>>, Vec<Locator>) {
+    let spans = find(text, paragraph_start);
+    if spans.is_empty() {
+        return (tokens, Vec::new());
+    }
+    let end_of = |t: &Token| t.offset + t.text.len() - base_offset;
+
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut locators = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let start = tokens[i].offset - base_offset;
+        let whole = spans.iter().find_map(|(range, locator)| {
+            if range.start != start {
+                return None;
+            }
+            let last = (i..tokens.len()).find(|&j| end_of(&tokens[j]) >= range.end)?;
+            (end_of(&tokens[last]) == range.end).then_some((last, locator))
+        });
+        let Some((last, locator)) = whole else {
+            out.push(tokens[i].clone());
+            i += 1;
+            continue;
+        };
+        let mut token = Token::spanning(
+            &tokens[i],
+            &tokens[last],
+            text,
+            base_offset,
+            locator.token_kind(),
+        );
+        token.value = numeral::parse(&locator.value);
+        out.push(token);
+        locators.push(Locator {
+            offset: base_offset + locator.offset,
+            ..locator.clone()
+        });
+        i = last + 1;
+    }
+    (out, locators)
+}
+
+/// True if `text` holds nothing but markers, so it belongs to the
+/// sentence that follows.
+pub fn is_marker_only(text: &str, paragraph_start: bool) -> bool {
+    let spans = find(text, paragraph_start);
+    let mut at = 0;
+    for (range, _) in &spans {
+        if !text[at..range.start].trim().is_empty() {
+            return false;
+        }
+        at = range.end;
+    }
+    !spans.is_empty() && text[at..].trim().is_empty()
+}
+
+// ---------------------------------------------------------
+// MARKER FORMS
+// ---------------------------------------------------------
+
+// Labels accepted inside brackets, matched case-insensitively.
+const EDITIONS: [(&str, LocatorKind); 10] = [
+    ("PTS", LocatorKind::Page),
+    ("page", LocatorKind::Page),
+    ("p", LocatorKind::Page),
+    ("M", LocatorKind::Page),
+    ("V", LocatorKind::Page),
+    ("P", LocatorKind::Page),
+    ("T", LocatorKind::Page),
+    ("VRI", LocatorKind::Para),
+    ("para", LocatorKind::Para),
+    ("¶", LocatorKind::Para),
+];
+
+// Single-letter CSCD page sigla (Myanmar, VRI, PTS, Thai) used without
+// brackets, as in "M.1.5".
+const BARE_EDITIONS: [char; 4] = ['M', 'V', 'P', 'T'];
+
+type Marker<'t> = (usize, LocatorKind, &'t str, &'t str);
+
+// "[PTS 1.2]", "[page 023]", "[M.0001]".
+fn bracketed(text: &str) -> Option<Marker<'_>> {
+    let close = text.find(']')?;
+    let inner = &text[1..close];
+    let split = inner.find(|c: char| c.is_whitespace() || c == '.')?;
+    let (edition, rest) = inner.split_at(split);
+    let kind = EDITIONS
+        .iter()
+        .find(|(e, _)| e.eq_ignore_ascii_case(edition))
+        .map(|&(_, kind)| kind)?;
+    let value = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '.');
+    (dotted_number(value) == Some(value.len())).then_some((close + 1, kind, edition, value))
+}
+
+// "M.1.5".
+fn bare(text: &str) -> Option<Marker<'_>> {
+    let edition = text.chars().next().filter(|c| BARE_EDITIONS.contains(c))?;
+    let value_start = edition.len_utf8() + 1;
+    if text.as_bytes().get(value_start - 1) != Some(&b'.') {
+        return None;
+    }
+    let len = dotted_number(&text[value_start..])?;
+    let end = value_start + len;
+    if text[end..]
+        .chars()
+        .next()
+        .is_some_and(char::is_alphanumeric)
+    {
+        return None;
+    }
+    Some((
+        end,
+        LocatorKind::Page,
+        &text[..edition.len_utf8()],
+        &text[value_start..end],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    #[test]
+    fn test_find_markers() {
+        let text = "1. Tena [PTS 1.2] samayena [page 023] buddho M.1.5 bhagavā [M.0001] viharati.";
+        let labels: Vec<String> = find(text, true).iter().map(|(_, l)| l.label()).collect();
+        assert_eq!(
+            labels,
+            vec!["¶ 1", "PTS 1.2", "page 023", "M 1.5", "M 0001"]
+        );
+        assert!(find("1. Tena", false).is_empty());
+        assert!(find("[dī. ni. 2.3] (M I 23)", true).is_empty());
+    }
+
+    #[test]
+    fn test_marker_tokens() {
+        let text = "Intro.\n\n1. Tena samayena [PTS 1.2] buddho bhagavā viharati.";
+        let result = process_text(text);
+        let sentence = &result.sentences[1];
+        assert_eq!(sentence.tokens[0].kind, TokenKind::ParaMarker);
+        assert_eq!(sentence.tokens[3].kind, TokenKind::PageMarker);
+        let labels: Vec<String> = sentence.locators.iter().map(Locator::label).collect();
+        assert_eq!(labels, vec!["¶ 1", "PTS 1.2"]);
+        assert_eq!(&text[sentence.locators[1].offset..][..9], "[PTS 1.2]");
+
+        let counts = result.word_frequencies();
+        assert!(counts.iter().all(|(w, _)| !w.contains("pts") && *w != "1"));
+    }
+}
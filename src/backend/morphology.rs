@@ -0,0 +1,283 @@
+use crate::backend::compound;
+use crate::backend::lexicon::Lexicon;
+use crate::backend::types::{SegResult, TokenKind};
+use std::sync::LazyLock;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Case {
+    Nominative,
+    Accusative,
+    Instrumental,
+    Dative,
+    Ablative,
+    Genitive,
+    Locative,
+    Vocative,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Number {
+    Singular,
+    Plural,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Gender {
+    Masculine,
+    Feminine,
+    Neuter,
+}
+
+impl Case {
+    pub fn abbrev(self) -> &'static str {
+        match self {
+            Case::Nominative => "nom",
+            Case::Accusative => "acc",
+            Case::Instrumental => "ins",
+            Case::Dative => "dat",
+            Case::Ablative => "abl",
+            Case::Genitive => "gen",
+            Case::Locative => "loc",
+            Case::Vocative => "voc",
+        }
+    }
+}
+
+impl Number {
+    pub fn abbrev(self) -> &'static str {
+        match self {
+            Number::Singular => "sg",
+            Number::Plural => "pl",
+        }
+    }
+}
+
+impl Gender {
+    pub fn abbrev(self) -> &'static str {
+        match self {
+            Gender::Masculine => "m",
+            Gender::Feminine => "f",
+            Gender::Neuter => "n",
+        }
+    }
+}
+
+/// One reading of a nominal form: stem plus vibhatti.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MorphAnalysis {
+    pub stem: String,
+    /// The case ending as written, e.g. "ehi".
+    pub ending: String,
+    pub case: Case,
+    pub number: Number,
+    pub gender: Gender,
+    /// True if the stem, or every member of it as a compound, is in the lexicon.
+    pub attested: bool,
+}
+
+impl MorphAnalysis {
+    /// Short gloss such as "bhikkhusata ins pl m".
+    pub fn label(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.stem,
+            self.case.abbrev(),
+            self.number.abbrev(),
+            self.gender.abbrev()
+        )
+    }
+}
+
+/// Possible nominal analyses of a normalized word. When any stem is
+/// attested only attested readings are returned.
+pub fn analyze(word: &str, lexicon: &Lexicon) -> Vec<MorphAnalysis> {
+    let mut out: Vec<MorphAnalysis> = Vec::new();
+    for row in table() {
+        let Some(stem) = stem_for(word, row) else {
+            continue;
+        };
+        let attested = match out.iter().find(|a| a.stem == stem) {
+            Some(seen) => seen.attested,
+            None => is_known_stem(&stem, lexicon),
+        };
+        out.push(MorphAnalysis {
+            stem,
+            ending: row.ending.clone(),
+            case: row.case,
+            number: row.number,
+            gender: row.gender,
+            attested,
+        });
+    }
+    if out.iter().any(|a| a.attested) {
+        out.retain(|a| a.attested);
+    }
+    // Longer endings first: "ehi" is a better guess than "i".
+    out.sort_by_key(|a| std::cmp::Reverse(a.ending.chars().count()));
+    out
+}
+
+/// Stems `word` could be an inflected form of, ignoring the lexicon.
+pub fn stem_candidates(word: &str) -> Vec<String> {
+    let mut stems: Vec<String> = Vec::new();
+    for row in table() {
+        if let Some(stem) = stem_for(word, row)
+            && !stems.contains(&stem)
+        {
+            stems.push(stem);
+        }
+    }
+    stems
+}
+
+/// Fills `morphology` on every word token.
+pub fn annotate(result: &mut SegResult, lexicon: &Lexicon) {
+    for sentence in result.segments_mut() {
+        for token in sentence.tokens.iter_mut() {
+            if token.kind == TokenKind::Word {
+                token.morphology = analyze(&token.normalized, lexicon);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------
+// DECLENSION TABLE
+// ---------------------------------------------------------
+
+const MIN_STEM_CHARS: usize = 3;
+
+struct Declension {
+    ending: String,
+    stem_final: String,
+    case: Case,
+    number: Number,
+    gender: Gender,
+}
+
+static TABLE: LazyLock<Vec<Declension>> =
+    LazyLock::new(|| parse_table(include_str!("../../data/declensions.tsv")));
+
+fn table() -> &'static [Declension] {
+    &TABLE
+}
+
+// In the lexicon, or a compound whose last member is itself a bare stem.
+fn is_known_stem(stem: &str, lexicon: &Lexicon) -> bool {
+    lexicon.contains(stem)
+        || compound::analyze(stem, lexicon).iter().any(|a| {
+            a.members
+                .last()
+                .is_some_and(|m| stem[m.offset..] == m.headword)
+        })
+}
+
+fn stem_for(word: &str, row: &Declension) -> Option<String> {
+    let base = word.strip_suffix(row.ending.as_str())?;
+    let stem = format!("{}{}", base, row.stem_final);
+    (!base.is_empty() && stem.chars().count() >= MIN_STEM_CHARS).then_some(stem)
+}
+
+// Tab-separated: ending, stem final, genders, cases, numbers.
+fn parse_table(text: &str) -> Vec<Declension> {
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let cols: Vec<&str> = line.split('\t').collect();
+        let [ending, stem_final, genders, cases, numbers] = cols[..] else {
+            continue;
+        };
+        for gender in genders.split('/').filter_map(parse_gender) {
+            for case in cases.split('/').filter_map(parse_case) {
+                for number in numbers.split('/').filter_map(parse_number) {
+                    rows.push(Declension {
+                        ending: ending.to_string(),
+                        stem_final: stem_final.to_string(),
+                        case,
+                        number,
+                        gender,
+                    });
+                }
+            }
+        }
+    }
+    rows
+}
+
+fn parse_case(s: &str) -> Option<Case> {
+    [
+        Case::Nominative,
+        Case::Accusative,
+        Case::Instrumental,
+        Case::Dative,
+        Case::Ablative,
+        Case::Genitive,
+        Case::Locative,
+        Case::Vocative,
+    ]
+    .into_iter()
+    .find(|c| c.abbrev() == s)
+}
+
+fn parse_number(s: &str) -> Option<Number> {
+    [Number::Singular, Number::Plural]
+        .into_iter()
+        .find(|n| n.abbrev() == s)
+}
+
+fn parse_gender(s: &str) -> Option<Gender> {
+    [Gender::Masculine, Gender::Feminine, Gender::Neuter]
+        .into_iter()
+        .find(|g| g.abbrev() == s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn readings(word: &str) -> Vec<(String, Case, Number)> {
+        analyze(word, Lexicon::bundled())
+            .into_iter()
+            .map(|a| (a.stem, a.case, a.number))
+            .collect()
+    }
+
+    #[test]
+    fn test_compound_stem_plural() {
+        let found = readings("bhikkhusatehi");
+        assert!(found.contains(&("bhikkhusata".into(), Case::Instrumental, Number::Plural)));
+        assert!(found.contains(&("bhikkhusata".into(), Case::Ablative, Number::Plural)));
+        assert!(found.iter().all(|(stem, _, _)| stem == "bhikkhusata"));
+    }
+
+    #[test]
+    fn test_consonant_stems() {
+        assert_eq!(
+            readings("bhagavā")[0],
+            ("bhagavant".into(), Case::Nominative, Number::Singular)
+        );
+        assert!(readings("mahatā").contains(&(
+            "mahant".into(),
+            Case::Instrumental,
+            Number::Singular
+        )));
+        assert!(
+            readings("satthā")
+                .iter()
+                .all(|(stem, _, _)| stem == "satthar")
+        );
+    }
+
+    #[test]
+    fn test_unattested_stems_are_kept() {
+        let found = analyze("nagaresu", Lexicon::bundled());
+        assert_eq!(found[0].label(), "nagara loc pl m");
+        assert!(!found[0].attested);
+    }
+}
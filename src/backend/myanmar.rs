@@ -0,0 +1,561 @@
+use crate::backend::types::{Diagnostic, OffsetMap, Severity};
+use std::borrow::Cow;
+use std::ops::Range;
+use unicode_general_category::{GeneralCategory, get_general_category};
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Probability above which a text is treated as Zawgyi and converted.
+pub const ZAWGYI_THRESHOLD: f32 = 0.5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preprocessed<'a> {
+    /// Standard Unicode text with marks in canonical storage order.
+    pub text: Cow<'a, str>,
+    /// 0.0 = certainly Unicode, 1.0 = certainly Zawgyi.
+    pub zawgyi_probability: f32,
+    pub converted: bool,
+    pub diagnostics: Vec<Diagnostic>,
+    pub offset_map: OffsetMap,
+}
+
+/// Detects Zawgyi, converts it to Unicode and reorders Myanmar marks into
+/// the canonical order of UTN #11. Text without Myanmar characters is
+/// returned borrowed and untouched.
+pub fn preprocess(text: &str) -> Preprocessed<'_> {
+    if !text.chars().any(is_myanmar) {
+        return Preprocessed {
+            text: Cow::Borrowed(text),
+            zawgyi_probability: 0.0,
+            converted: false,
+            diagnostics: Vec::new(),
+            offset_map: OffsetMap::default(),
+        };
+    }
+
+    let zawgyi_probability = zawgyi_probability(text);
+    let converted = zawgyi_probability >= ZAWGYI_THRESHOLD;
+    let units = if converted {
+        zawgyi_units(text)
+    } else {
+        unicode_units(text)
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut diagnostics = Vec::new();
+    let mut offset_map = OffsetMap::default();
+
+    // Zawgyi changes nearly every cluster, so it gets one summary.
+    let mut converted_clusters = 0;
+    for cluster in build_clusters(units, converted) {
+        let start = out.len();
+        cluster.write(&mut out);
+        let original = &text[cluster.src.clone()];
+        if &out[start..] == original {
+            continue;
+        }
+        offset_map.push(cluster.src.clone(), start..out.len());
+        if converted {
+            converted_clusters += 1;
+            continue;
+        }
+
+        let message = if cluster.substituted {
+            format!("Replaced digit zero with letter wa in \"{}\"", original)
+        } else {
+            format!("Reordered marks in \"{}\"", original)
+        };
+        diagnostics.push(Diagnostic {
+            offset: cluster.src.start,
+            len: cluster.src.len(),
+            severity: Severity::Info,
+            message,
+        });
+    }
+
+    if converted {
+        diagnostics.push(Diagnostic {
+            offset: 0,
+            len: text.len(),
+            severity: Severity::Warning,
+            message: format!(
+                "Zawgyi encoding detected (confidence {:.2}), converted {} cluster{} to Unicode",
+                zawgyi_probability,
+                converted_clusters,
+                if converted_clusters == 1 { "" } else { "s" }
+            ),
+        });
+    }
+
+    let text = if offset_map.is_identity() {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(out)
+    };
+
+    Preprocessed {
+        text,
+        zawgyi_probability,
+        converted,
+        diagnostics,
+        offset_map,
+    }
+}
+
+/// Heuristic Zawgyi score in `0.0..=1.0` from code points and orderings
+/// that only occur in one of the two encodings.
+pub fn zawgyi_probability(text: &str) -> f32 {
+    let chars: Vec<char> = text.chars().collect();
+    let mut zawgyi = 0.0f32;
+    let mut unicode = 0.0f32;
+
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1).copied();
+        match c {
+            // Zawgyi presentation forms; Burmese-script Pali never needs these.
+            '\u{1060}'..='\u{1097}' => zawgyi += 1.0,
+            // Vowel sign E and Zawgyi medial ra are typed before the consonant.
+            '\u{1031}' | '\u{103B}' => {
+                if prev.is_some_and(|p| is_consonant(p) || is_medial(p)) {
+                    unicode += 1.0;
+                } else {
+                    zawgyi += 2.0;
+                }
+            }
+            // Unicode virama stacks a consonant; Zawgyi uses U+1039 as asat.
+            '\u{1039}' => {
+                if next.is_some_and(is_consonant) {
+                    unicode += 0.5;
+                } else {
+                    zawgyi += 1.0;
+                }
+            }
+            // Kinzi is spelled out as nga + asat + virama only in Unicode.
+            '\u{1004}' if chars.get(i + 1..i + 3) == Some(&['\u{103A}', '\u{1039}']) => {
+                unicode += 2.0;
+            }
+            _ => {}
+        }
+    }
+
+    if zawgyi == 0.0 {
+        return 0.0;
+    }
+    zawgyi / (zawgyi + unicode)
+}
+
+/// Splits Myanmar text into orthographic syllables, returned as byte ranges.
+/// A consonant starts a new syllable unless it is stacked (after a virama)
+/// or closes the current one (followed by asat or virama), which keeps
+/// kinzi and stacked clusters together.
+pub fn syllables(text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut starts = Vec::new();
+
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        let starts_syllable = match i.checked_sub(1).map(|j| chars[j].1) {
+            None => true,
+            Some(prev) if !is_myanmar(prev) || !is_myanmar(c) => true,
+            Some(prev) if is_consonant(c) => {
+                // Dot below may sit between a final consonant and its asat.
+                let next = chars[i + 1..]
+                    .iter()
+                    .map(|&(_, n)| n)
+                    .find(|&n| n != '\u{1037}');
+                prev != '\u{1039}' && !matches!(next, Some('\u{103A}' | '\u{1039}'))
+            }
+            Some(_) => !is_mark(c) && c != '\u{1039}',
+        };
+        if starts_syllable {
+            starts.push(offset);
+        }
+    }
+
+    let mut ranges: Vec<Range<usize>> = starts.windows(2).map(|w| w[0]..w[1]).collect();
+    if let Some(&last) = starts.last() {
+        ranges.push(last..text.len());
+    }
+    ranges
+}
+
+pub fn is_myanmar(c: char) -> bool {
+    matches!(c, '\u{1000}'..='\u{109F}' | '\u{A9E0}'..='\u{A9FF}' | '\u{AA60}'..='\u{AA7F}')
+}
+
+// ---------------------------------------------------------
+// CHARACTER CLASSES
+// ---------------------------------------------------------
+
+fn is_consonant(c: char) -> bool {
+    matches!(c, '\u{1000}'..='\u{1021}' | '\u{1050}' | '\u{1051}')
+}
+
+fn is_base(c: char) -> bool {
+    matches!(c, '\u{1000}'..='\u{102A}' | '\u{103F}' | '\u{104C}'..='\u{104F}' | '\u{1050}'..='\u{1055}')
+}
+
+fn is_medial(c: char) -> bool {
+    matches!(c, '\u{103B}'..='\u{103E}')
+}
+
+fn is_mark(c: char) -> bool {
+    is_myanmar(c)
+        && matches!(
+            get_general_category(c),
+            GeneralCategory::NonspacingMark | GeneralCategory::SpacingMark
+        )
+}
+
+// ---------------------------------------------------------
+// UNITS AND CLUSTERS
+// ---------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Base(char),
+    Mark(char),
+    /// Virama + consonant.
+    Stack(char),
+    /// Nga + asat + virama, written before the consonant it sits on.
+    Kinzi,
+    Other(char),
+}
+
+#[derive(Debug, Clone)]
+struct Sourced {
+    unit: Unit,
+    src: Range<usize>,
+    substituted: bool,
+}
+
+const KINZI: &str = "\u{1004}\u{103A}\u{1039}";
+
+// Canonical storage order from UTN #11.
+fn mark_rank(unit: Unit) -> u8 {
+    match unit {
+        Unit::Stack(_) => 1,
+        Unit::Mark(c) => match c {
+            '\u{103B}' => 2,
+            '\u{103C}' => 3,
+            '\u{103D}' => 4,
+            '\u{103E}' => 5,
+            '\u{1031}' => 6,
+            '\u{102D}' | '\u{102E}' | '\u{1032}' => 7,
+            '\u{102F}' | '\u{1030}' => 8,
+            '\u{102B}' | '\u{102C}' => 9,
+            '\u{1036}' => 10,
+            '\u{1037}' => 11,
+            '\u{103A}' => 12,
+            '\u{1038}' => 13,
+            _ => 14,
+        },
+        _ => 14,
+    }
+}
+
+fn unicode_units(text: &str) -> Vec<Sourced> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let end_of = |i: usize| chars.get(i).map_or(text.len(), |&(o, _)| o);
+    let char_at = |i: usize| chars.get(i).map(|&(_, c)| c);
+
+    let mut units = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        let (unit, consumed, substituted) = if c == '\u{1004}'
+            && char_at(i + 1) == Some('\u{103A}')
+            && char_at(i + 2) == Some('\u{1039}')
+            && char_at(i + 3).is_some_and(is_consonant)
+        {
+            (Unit::Kinzi, 3, false)
+        } else if c == '\u{1039}' && char_at(i + 1).is_some_and(is_consonant) {
+            (Unit::Stack(char_at(i + 1).unwrap()), 2, false)
+        } else if c == '\u{1040}' && char_at(i + 1).is_some_and(is_mark) {
+            // Digit zero typed in place of the look-alike letter wa.
+            (Unit::Base('\u{101D}'), 1, true)
+        } else if is_base(c) {
+            (Unit::Base(c), 1, false)
+        } else if is_mark(c) {
+            (Unit::Mark(c), 1, false)
+        } else {
+            (Unit::Other(c), 1, false)
+        };
+        units.push(Sourced {
+            unit,
+            src: offset..end_of(i + consumed),
+            substituted,
+        });
+        i += consumed;
+    }
+    units
+}
+
+fn zawgyi_char(c: char) -> Option<&'static [Unit]> {
+    use Unit::*;
+    let units: &'static [Unit] = match c {
+        '\u{1039}' => &[Mark('\u{103A}')],
+        '\u{103A}' | '\u{107D}' => &[Mark('\u{103B}')],
+        '\u{103B}' | '\u{107E}'..='\u{1084}' => &[Mark('\u{103C}')],
+        '\u{103C}' => &[Mark('\u{103D}')],
+        '\u{103D}' | '\u{1087}' => &[Mark('\u{103E}')],
+        '\u{1088}' => &[Mark('\u{103E}'), Mark('\u{102F}')],
+        '\u{1089}' => &[Mark('\u{103E}'), Mark('\u{1030}')],
+        '\u{108A}' => &[Mark('\u{103D}'), Mark('\u{103E}')],
+        '\u{1033}' => &[Mark('\u{102F}')],
+        '\u{1034}' => &[Mark('\u{1030}')],
+        '\u{105A}' => &[Mark('\u{102B}'), Mark('\u{103A}')],
+        '\u{1064}' => &[Kinzi],
+        '\u{108B}' => &[Kinzi, Mark('\u{102D}')],
+        '\u{108C}' => &[Kinzi, Mark('\u{102E}')],
+        '\u{108D}' => &[Kinzi, Mark('\u{1036}')],
+        '\u{108E}' => &[Mark('\u{102D}'), Mark('\u{1036}')],
+        '\u{1094}' | '\u{1095}' => &[Mark('\u{1037}')],
+        '\u{106A}' => &[Base('\u{1009}')],
+        '\u{106B}' => &[Base('\u{100A}')],
+        '\u{108F}' => &[Base('\u{1014}')],
+        '\u{1090}' => &[Base('\u{101B}')],
+        '\u{1086}' => &[Base('\u{103F}')],
+        '\u{1060}' => &[Stack('\u{1000}')],
+        '\u{1061}' => &[Stack('\u{1001}')],
+        '\u{1062}' => &[Stack('\u{1002}')],
+        '\u{1063}' => &[Stack('\u{1003}')],
+        '\u{1065}' => &[Stack('\u{1005}')],
+        '\u{1066}' | '\u{1067}' => &[Stack('\u{1006}')],
+        '\u{1068}' => &[Stack('\u{1007}')],
+        '\u{1069}' => &[Stack('\u{1008}')],
+        '\u{106C}' => &[Stack('\u{100B}')],
+        '\u{106D}' => &[Stack('\u{100C}')],
+        '\u{1070}' => &[Stack('\u{100F}')],
+        '\u{1071}' | '\u{1072}' => &[Stack('\u{1010}')],
+        '\u{1073}' | '\u{1074}' => &[Stack('\u{1011}')],
+        '\u{1075}' => &[Stack('\u{1012}')],
+        '\u{1076}' => &[Stack('\u{1013}')],
+        '\u{1077}' => &[Stack('\u{1014}')],
+        '\u{1078}' => &[Stack('\u{1015}')],
+        '\u{1079}' => &[Stack('\u{1016}')],
+        '\u{107A}' => &[Stack('\u{1017}')],
+        '\u{107B}' | '\u{1093}' => &[Stack('\u{1018}')],
+        '\u{107C}' => &[Stack('\u{1019}')],
+        '\u{1085}' => &[Stack('\u{101C}')],
+        '\u{1096}' => &[Stack('\u{1010}'), Mark('\u{103D}')],
+        '\u{106E}' => &[Base('\u{100D}'), Stack('\u{100D}')],
+        '\u{106F}' => &[Base('\u{100D}'), Stack('\u{100E}')],
+        '\u{1091}' => &[Base('\u{100F}'), Stack('\u{100D}')],
+        '\u{1092}' => &[Base('\u{100B}'), Stack('\u{100C}')],
+        '\u{1097}' => &[Base('\u{100B}'), Stack('\u{100B}')],
+        _ => return None,
+    };
+    Some(units)
+}
+
+fn zawgyi_units(text: &str) -> Vec<Sourced> {
+    let mut units = Vec::with_capacity(text.len() / 2);
+    for (offset, c) in text.char_indices() {
+        let src = offset..offset + c.len_utf8();
+        if let Some(mapped) = zawgyi_char(c) {
+            units.extend(mapped.iter().map(|&unit| Sourced {
+                unit,
+                src: src.clone(),
+                substituted: false,
+            }));
+            continue;
+        }
+        let unit = if is_base(c) {
+            Unit::Base(c)
+        } else if is_mark(c) {
+            Unit::Mark(c)
+        } else {
+            Unit::Other(c)
+        };
+        units.push(Sourced {
+            unit,
+            src,
+            substituted: false,
+        });
+    }
+    units
+}
+
+#[derive(Debug)]
+struct Cluster {
+    kinzi: bool,
+    base: Option<Unit>,
+    marks: Vec<Unit>,
+    src: Range<usize>,
+    substituted: bool,
+}
+
+impl Cluster {
+    fn new(src: Range<usize>) -> Self {
+        Self {
+            kinzi: false,
+            base: None,
+            marks: Vec::new(),
+            src,
+            substituted: false,
+        }
+    }
+
+    fn absorb(&mut self, sourced: &Sourced) {
+        self.src.start = self.src.start.min(sourced.src.start);
+        self.src.end = self.src.end.max(sourced.src.end);
+        self.substituted |= sourced.substituted;
+        match sourced.unit {
+            Unit::Kinzi => self.kinzi = true,
+            Unit::Mark(_) | Unit::Stack(_) => self.marks.push(sourced.unit),
+            unit => self.base = Some(unit),
+        }
+    }
+
+    fn takes_marks(&self) -> bool {
+        matches!(self.base, Some(Unit::Base(_)))
+    }
+
+    fn write(&self, out: &mut String) {
+        if self.kinzi {
+            out.push_str(KINZI);
+        }
+        if let Some(Unit::Base(c) | Unit::Other(c)) = self.base {
+            out.push(c);
+        }
+        let mut marks = self.marks.clone();
+        marks.sort_by_key(|&m| mark_rank(m));
+        for mark in marks {
+            match mark {
+                Unit::Stack(c) => {
+                    out.push('\u{1039}');
+                    out.push(c);
+                }
+                Unit::Mark(c) => out.push(c),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Groups units into orthographic syllable clusters. In Zawgyi the vowel
+/// sign E and medial ra precede their consonant and kinzi follows it, so
+/// they are carried over to the right cluster here.
+fn build_clusters(units: Vec<Sourced>, zawgyi: bool) -> Vec<Cluster> {
+    let mut clusters: Vec<Cluster> = Vec::new();
+    let mut pending: Vec<Sourced> = Vec::new();
+
+    for sourced in units {
+        match sourced.unit {
+            Unit::Base(_) => {
+                let mut cluster = Cluster::new(sourced.src.clone());
+                for p in pending.drain(..) {
+                    cluster.absorb(&p);
+                }
+                cluster.absorb(&sourced);
+                clusters.push(cluster);
+            }
+            Unit::Kinzi if !zawgyi => pending.push(sourced),
+            Unit::Mark('\u{1031}' | '\u{103C}') if zawgyi => pending.push(sourced),
+            Unit::Mark(_) | Unit::Stack(_) | Unit::Kinzi
+                if pending.is_empty() && clusters.last().is_some_and(Cluster::takes_marks) =>
+            {
+                clusters.last_mut().unwrap().absorb(&sourced);
+            }
+            _ => {
+                // Orphaned prefixes or marks stay where they were.
+                for p in pending.drain(..) {
+                    let mut cluster = Cluster::new(p.src.clone());
+                    cluster.absorb(&p);
+                    clusters.push(cluster);
+                }
+                let mut cluster = Cluster::new(sourced.src.clone());
+                cluster.absorb(&sourced);
+                clusters.push(cluster);
+            }
+        }
+    }
+
+    for p in pending {
+        let mut cluster = Cluster::new(p.src.clone());
+        cluster.absorb(&p);
+        clusters.push(cluster);
+    }
+
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_text_is_untouched() {
+        let text = "၁။ တေန သမယေန ဗုဒ္ဓေါ ဘဂဝါ ဝေရဉ္ဇာယံ ဝိဟရတိ။";
+        let result = preprocess(text);
+
+        assert_eq!(result.zawgyi_probability, 0.0);
+        assert!(!result.converted);
+        assert!(matches!(result.text, Cow::Borrowed(_)));
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_zawgyi_conversion() {
+        // Zawgyi spelling of "ဗုဒ္ဓေါ": E before the stack, presentation-form dha.
+        let zawgyi = "\u{1017}\u{102F}\u{1031}\u{1012}\u{1076}\u{102B}";
+        let result = preprocess(zawgyi);
+
+        assert!(result.converted, "p = {}", result.zawgyi_probability);
+        assert_eq!(result.text, "ဗုဒ္ဓေါ");
+        assert_eq!(result.diagnostics.len(), 1);
+
+        // One summary however many clusters change.
+        let twice = format!("{} {}", zawgyi, zawgyi);
+        let result = preprocess(&twice);
+        assert_eq!(result.diagnostics.len(), 1);
+        assert!(
+            result.diagnostics[0]
+                .message
+                .contains("converted 2 clusters")
+        );
+    }
+
+    #[test]
+    fn test_mark_reordering_and_offset_map() {
+        // "င့်" typed with asat before dot below; canonical is dot below first.
+        let text = "ဝ င\u{103A}\u{1037} က";
+        let result = preprocess(text);
+
+        assert!(!result.converted);
+        assert_eq!(result.text, "ဝ င\u{1037}\u{103A} က");
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].offset, "ဝ ".len());
+
+        let ka = result.text.find('က').unwrap();
+        assert_eq!(result.offset_map.to_original(ka), text.find('က').unwrap());
+    }
+
+    #[test]
+    fn test_syllables() {
+        let split = |text: &str| -> Vec<String> {
+            syllables(text)
+                .into_iter()
+                .map(|r| text[r].to_string())
+                .collect()
+        };
+
+        assert_eq!(split("ဘဂဝါ"), vec!["ဘ", "ဂ", "ဝါ"]);
+        assert_eq!(split("ဝေရဉ္ဇာယံ"), vec!["ဝေ", "ရဉ္ဇာ", "ယံ"]);
+        // Kinzi and the final consonant with dot below stay in one syllable.
+        assert_eq!(split("သင်္ဃော"), vec!["သင်္ဃော"]);
+        assert_eq!(split("ဝင့်ပါ"), vec!["ဝင့်", "ပါ"]);
+    }
+
+    #[test]
+    fn test_kinzi_is_preserved() {
+        let text = "သင်္ဃော";
+        let result = preprocess(text);
+
+        assert_eq!(result.text, text);
+        assert!(result.diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+use std::borrow::Cow;
+use unicode_normalization::{UnicodeNormalization, is_nfc};
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// The niggahīta every variant is unified to.
+pub const NIGGAHITA: char = 'ṃ';
+
+/// Edition-neutral form of a Pali word: NFC, a single niggahīta and lower
+/// case. Returns the input borrowed when it is already normalized.
+pub fn normalize_pali(text: &str) -> Cow<'_, str> {
+    if is_nfc(text) && !text.chars().any(needs_mapping) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for c in text.nfc() {
+        match c {
+            // ṁ (dot above), ŋ (eng) and their capitals are all niggahīta.
+            'ṁ' | 'Ṁ' | 'ŋ' | 'Ŋ' | 'Ṃ' => out.push(NIGGAHITA),
+            c if c.is_uppercase() => out.extend(c.to_lowercase()),
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn needs_mapping(c: char) -> bool {
+    matches!(c, 'ṁ' | 'Ṁ' | 'ŋ' | 'Ŋ') || c.is_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_niggahita_variants() {
+        let variants = ["saṃ", "saṁ", "saŋ", "sam\u{0323}", "sam\u{0307}", "SAṂ"];
+        for v in variants {
+            assert_eq!(normalize_pali(v), "saṃ", "variant {:?}", v);
+        }
+    }
+
+    #[test]
+    fn test_decomposed_consonants() {
+        // ñ, ṅ, ṭ, ḍ, ṇ, ḷ written with combining marks
+        let nfd = "n\u{0303}n\u{0307}t\u{0323}d\u{0323}n\u{0323}l\u{0323}";
+        assert_eq!(normalize_pali(nfd), "ñṅṭḍṇḷ");
+    }
+
+    #[test]
+    fn test_normalized_text_is_borrowed() {
+        assert!(matches!(
+            normalize_pali("bhikkhusaṅghena"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(normalize_pali("Tena"), Cow::Owned(_)));
+    }
+}
@@ -0,0 +1,417 @@
+use crate::backend::morphology;
+use crate::backend::types::{Token, TokenKind};
+use std::fmt;
+use std::sync::LazyLock;
+use unicode_general_category::{GeneralCategory, get_general_category};
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Parsed value of a number such as "157", "၁၅၇" or "1.157", one entry
+/// per dot-separated part, so values sort as section numbers do
+/// (1.16 before 1.157).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NumericValue(pub Vec<u64>);
+
+impl NumericValue {
+    /// The value of a plain, undotted number.
+    pub fn as_integer(&self) -> Option<u64> {
+        match self.0[..] {
+            [n] => Some(n),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for NumericValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(u64::to_string).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+/// Value of a decimal digit in any script (Latin, Devanagari, Sinhala,
+/// Thai, Myanmar, …).
+pub fn digit_value(c: char) -> Option<u64> {
+    if c.is_ascii_digit() {
+        return Some(c as u64 - '0' as u64);
+    }
+    if !is_digit(c) {
+        return None;
+    }
+    // Unicode lays decimal digits out in runs of ten starting at zero,
+    // sometimes several runs back to back.
+    let mut first = c as u32;
+    while char::from_u32(first - 1).is_some_and(is_digit) {
+        first -= 1;
+    }
+    Some(((c as u32 - first) % 10) as u64)
+}
+
+/// Length of a number such as "157", "१५७" or "1.157.3" at the start of
+/// `text`. Dots only count between digits.
+pub fn dotted_number(text: &str) -> Option<usize> {
+    let digits_at = |at: usize| {
+        text[at..]
+            .chars()
+            .take_while(|&c| is_digit(c))
+            .map(char::len_utf8)
+            .sum::<usize>()
+    };
+    let mut len = 0;
+    loop {
+        let digits = digits_at(len);
+        if digits == 0 {
+            break;
+        }
+        len += digits;
+        if text[len..].starts_with('.') && digits_at(len + 1) > 0 {
+            len += 1;
+        } else {
+            break;
+        }
+    }
+    (len > 0).then_some(len)
+}
+
+/// Parses a whole text as a number, e.g. "023", "๑๕๗" or "1.157".
+pub fn parse(text: &str) -> Option<NumericValue> {
+    if dotted_number(text) != Some(text.len()) {
+        return None;
+    }
+    text.split('.')
+        .map(|part| {
+            part.chars()
+                .try_fold(0u64, |n, c| n.checked_mul(10)?.checked_add(digit_value(c)?))
+        })
+        .collect::<Option<Vec<u64>>>()
+        .map(NumericValue)
+}
+
+/// Value of a Roman numeral in i, v and x, all upper or all lower case
+/// ("IV", "xii").
+pub fn roman_value(s: &str) -> Option<u64> {
+    let upper = s.chars().all(|c| matches!(c, 'I' | 'V' | 'X'));
+    let lower = s.chars().all(|c| matches!(c, 'i' | 'v' | 'x'));
+    if s.is_empty() || !(upper || lower) {
+        return None;
+    }
+    let digit = |c: char| match c.to_ascii_lowercase() {
+        'i' => 1,
+        'v' => 5,
+        _ => 10,
+    };
+    // Right to left: a digit smaller than the one after it subtracts.
+    let mut total = 0;
+    let mut after = 0;
+    for v in s.chars().rev().map(digit) {
+        if v < after {
+            total -= v;
+        } else {
+            total += v;
+        }
+        after = v;
+    }
+    Some(total)
+}
+
+/// A Pali number word read as a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberWord {
+    pub value: u64,
+    pub ordinal: bool,
+    /// Formed with -matta, "about".
+    pub approximate: bool,
+    // A hundred, thousand, … of something (bhikkhusata), to be multiplied
+    // by a number word just before it.
+    counted: bool,
+    // Also an ordinary word (satta, "being"), read as a number only next
+    // to another number.
+    homonym: bool,
+}
+
+/// Reads a normalized word as a Pali number word: cardinals in any case,
+/// ordinals, units with tens (dvāsīti = 82), multiples (pañcasata = 500),
+/// "about" forms in -matta, and counted hundreds or thousands
+/// (bhikkhusata).
+pub fn number_word(word: &str) -> Option<NumberWord> {
+    let mut candidates = vec![word.to_string()];
+    candidates.extend(morphology::stem_candidates(word));
+    candidates.iter().find_map(|stem| {
+        let (stem, approximate) = match stem.strip_suffix("matta") {
+            Some(base) if !base.is_empty() => (base, true),
+            _ => (stem.as_str(), false),
+        };
+        let found = compound_value(stem)?;
+        Some(NumberWord {
+            approximate,
+            homonym: found.homonym && !approximate,
+            ..found
+        })
+    })
+}
+
+/// Sets `value` on number words. A counted hundred or thousand right
+/// after a smaller number takes the product, so "pañcamattehi
+/// bhikkhusatehi" is 500. Particles in between are skipped. Homonyms such
+/// as satta and sata get a value only beside a number, as in "satta
+/// bhikkhusatāni" or "sataṃ sahassāni".
+pub fn tag_words(tokens: &mut [Token]) {
+    let words: Vec<Option<NumberWord>> = tokens
+        .iter()
+        .map(|t| {
+            (t.kind == TokenKind::Word)
+                .then(|| number_word(&t.normalized))
+                .flatten()
+        })
+        .collect();
+    let is_number =
+        |j: usize| tokens[j].kind == TokenKind::Number || words[j].is_some_and(|w| !w.homonym);
+    let next_to_number: Vec<bool> = (0..tokens.len())
+        .map(|i| {
+            let before = (0..i)
+                .rev()
+                .find(|&j| tokens[j].kind != TokenKind::Particle);
+            let after = (i + 1..tokens.len()).find(|&j| tokens[j].kind != TokenKind::Particle);
+            before.is_some_and(is_number) || after.is_some_and(is_number)
+        })
+        .collect();
+
+    let mut previous: Option<u64> = None;
+    for (i, token) in tokens.iter_mut().enumerate() {
+        match token.kind {
+            TokenKind::Particle => continue,
+            TokenKind::Number => {
+                previous = token.value.as_ref().and_then(NumericValue::as_integer);
+                continue;
+            }
+            _ => {}
+        }
+        let Some(word) = words[i].filter(|w| !w.homonym || next_to_number[i]) else {
+            previous = None;
+            continue;
+        };
+        let value = match previous {
+            Some(n) if word.counted => n * word.value,
+            _ => word.value,
+        };
+        token.value = Some(NumericValue(vec![value]));
+        previous = (!word.ordinal && word.value < 100).then_some(word.value);
+    }
+}
+
+fn is_digit(c: char) -> bool {
+    c.is_ascii_digit() || get_general_category(c) == GeneralCategory::DecimalNumber
+}
+
+// ---------------------------------------------------------
+// NUMBER WORDS
+// ---------------------------------------------------------
+
+const MIN_COUNTED_HEAD_CHARS: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Cardinal,
+    Unit,
+    Ordinal,
+}
+
+// (form, value, kind, homonym)
+static TABLE: LazyLock<Vec<(String, u64, Kind, bool)>> =
+    LazyLock::new(|| parse_table(include_str!("../../data/numerals.tsv")));
+
+fn entry(form: &str, kind: Kind) -> Option<(u64, bool)> {
+    TABLE
+        .iter()
+        .find(|(f, _, k, _)| *k == kind && f == form)
+        .map(|&(_, value, _, homonym)| (value, homonym))
+}
+
+fn lookup(form: &str, kind: Kind) -> Option<u64> {
+    entry(form, kind).map(|(value, _)| value)
+}
+
+fn cardinal(value: u64) -> NumberWord {
+    NumberWord {
+        value,
+        ordinal: false,
+        approximate: false,
+        counted: false,
+        homonym: false,
+    }
+}
+
+fn compound_value(stem: &str) -> Option<NumberWord> {
+    if let Some((value, homonym)) = entry(stem, Kind::Cardinal) {
+        return Some(NumberWord {
+            homonym,
+            ..cardinal(value)
+        });
+    }
+    if let Some(value) = lookup(stem, Kind::Ordinal) {
+        return Some(NumberWord {
+            ordinal: true,
+            ..cardinal(value)
+        });
+    }
+    // Longest tail first, so "bhikkhusatasahassa" is a hundred thousand.
+    for (i, _) in stem.char_indices().skip(1) {
+        let (head, tail) = stem.split_at(i);
+        if let (Some(unit), Some(ten)) = (unit_value(head), ten_value(tail)) {
+            return Some(cardinal(unit + ten));
+        }
+        let Some(base) = lookup(tail, Kind::Cardinal).filter(|&b| b >= 100) else {
+            continue;
+        };
+        if let Some(n) = lookup(head, Kind::Cardinal).or_else(|| unit_value(head)) {
+            return Some(cardinal(n * base));
+        }
+        if head.chars().count() >= MIN_COUNTED_HEAD_CHARS {
+            return Some(NumberWord {
+                counted: true,
+                ..cardinal(base)
+            });
+        }
+    }
+    None
+}
+
+// "dv" (dvā), "catur", "aṭṭha" before a ten.
+fn unit_value(head: &str) -> Option<u64> {
+    [head.to_string(), format!("{}a", head), format!("{}ā", head)]
+        .iter()
+        .find_map(|form| lookup(form, Kind::Unit))
+}
+
+// A ten after a unit: "asīti" as "āsīti" (dvāsīti) and "tiṃsa" as
+// "ttiṃsa" (tettiṃsa) too.
+fn ten_value(tail: &str) -> Option<u64> {
+    let mut forms = vec![tail.to_string()];
+    if let Some(rest) = tail.strip_prefix('ā') {
+        forms.push(format!("a{}", rest));
+    }
+    let mut chars = tail.chars();
+    if let (Some(a), Some(b)) = (chars.next(), chars.next())
+        && a == b
+    {
+        forms.push(tail[a.len_utf8()..].to_string());
+    }
+    forms
+        .iter()
+        .filter_map(|form| lookup(form, Kind::Cardinal))
+        .find(|&v| (20..100).contains(&v) && v % 10 == 0)
+}
+
+// Tab-separated: form, value, kind, and "homonym" for words that are
+// also something else.
+fn parse_table(text: &str) -> Vec<(String, u64, Kind, bool)> {
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let cols: Vec<&str> = line.split('\t').map(str::trim).collect();
+        let (form, value, kind, homonym) = match cols[..] {
+            [form, value, kind] => (form, value, kind, false),
+            [form, value, kind, flag] => (form, value, kind, flag == "homonym"),
+            _ => continue,
+        };
+        let kind = match kind {
+            "card" => Kind::Cardinal,
+            "unit" => Kind::Unit,
+            "ord" => Kind::Ordinal,
+            _ => continue,
+        };
+        if let Ok(value) = value.parse() {
+            rows.push((form.to_string(), value, kind, homonym));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+    use crate::backend::types::TokenKind;
+
+    #[test]
+    fn test_digits_in_all_scripts() {
+        for text in ["157", "१५७", "၁၅၇", "๑๕๗", "෧෫෭", "១៥៧"] {
+            assert_eq!(parse(text), Some(NumericValue(vec![157])), "{}", text);
+        }
+        assert_eq!(parse("023").and_then(|v| v.as_integer()), Some(23));
+        assert_eq!(parse("1.157"), Some(NumericValue(vec![1, 157])));
+        assert_eq!(parse("1."), None);
+        assert_eq!(parse("abc"), None);
+        assert_eq!(dotted_number("1.157, abbhuggata"), Some(5));
+
+        let result = process_text("Pañca ၁၅၇ bhikkhū, १२ ca 1.157.");
+        let numbers: Vec<String> = result.sentences[0]
+            .tokens
+            .iter()
+            .filter(|t| t.kind == TokenKind::Number)
+            .filter_map(|t| t.value.as_ref().map(|v| v.to_string()))
+            .collect();
+        assert_eq!(numbers, vec!["157", "12", "1.157"]);
+    }
+
+    #[test]
+    fn test_number_words() {
+        let value = |word: &str| number_word(word).map(|w| w.value);
+        assert_eq!(value("pañca"), Some(5));
+        assert_eq!(value("dvāsīti"), Some(82));
+        assert_eq!(value("caturāsīti"), Some(84));
+        assert_eq!(value("tettiṃsa"), Some(33));
+        assert_eq!(value("pañcasatāni"), Some(500));
+        assert_eq!(value("sahassaṃ"), Some(1000));
+        assert!(number_word("tatiyaṃ").is_some_and(|w| w.ordinal && w.value == 3));
+        assert!(number_word("pañcamattehi").is_some_and(|w| w.approximate && w.value == 5));
+        assert_eq!(value("bhagavā"), None);
+
+        let result = process_text(
+            "Mahatā bhikkhusaṅghena saddhiṃ pañcamattehi bhikkhusatehi. \
+             Caturāsīti pāṇasahassāni. Pañca kho bhikkhusatāni.",
+        );
+        let mentions: Vec<&str> = result
+            .mentioning(500)
+            .iter()
+            .map(|s| s.text.trim())
+            .collect();
+        assert_eq!(
+            mentions,
+            vec![
+                "Mahatā bhikkhusaṅghena saddhiṃ pañcamattehi bhikkhusatehi.",
+                "Pañca kho bhikkhusatāni."
+            ]
+        );
+        assert_eq!(result.mentioning(84000).len(), 1);
+
+        // satta "being", sata "mindful" without a number beside them
+        let result = process_text("Sato satta. Satta bhikkhusatāni.");
+        let values: Vec<Vec<String>> = result
+            .sentences
+            .iter()
+            .map(|s| {
+                s.tokens
+                    .iter()
+                    .filter_map(|t| t.value.as_ref().map(|v| v.to_string()))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(values, vec![vec![], vec!["7", "700"]]);
+    }
+
+    #[test]
+    fn test_section_order() {
+        let mut values: Vec<NumericValue> = ["1.157", "2", "1.16", "1.2"]
+            .iter()
+            .filter_map(|t| parse(t))
+            .collect();
+        values.sort();
+        let sorted: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        assert_eq!(sorted, vec!["1.2", "1.16", "1.157", "2"]);
+    }
+}
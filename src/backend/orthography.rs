@@ -0,0 +1,204 @@
+use crate::backend::normalize::{NIGGAHITA, normalize_pali};
+use crate::backend::translit::ascii_fold;
+use crate::backend::types::Token;
+use std::borrow::Cow;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// A spelling difference between editions that the key ignores. Rules
+/// apply to normalized Roman text; other scripts pass through.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rule {
+    /// A class nasal before a stop of its class is written ṃ, so saṅgha,
+    /// saṃgha and sammāsambuddho, sammāsaṃbuddho each key alike.
+    NasalToNiggahita,
+    /// Doubled consonants count once: sakkacca = sakacca.
+    Geminates,
+    /// ḷ and ḷh count as ḍ and ḍh: āḷhaka = āḍhaka.
+    RetroflexLateral,
+    /// Any other spelling, replaced wherever it occurs.
+    Substitute(String, String),
+}
+
+impl Rule {
+    // Applies the rule, or returns `None` if it changes nothing.
+    fn apply(&self, text: &str) -> Option<String> {
+        match self {
+            Rule::NasalToNiggahita => {
+                let mut chars = text.chars().peekable();
+                let mut out = String::with_capacity(text.len());
+                let mut changed = false;
+                while let Some(c) = chars.next() {
+                    let before_stop = chars.peek().is_some_and(|&next| homorganic(c, next));
+                    changed |= before_stop;
+                    out.push(if before_stop { NIGGAHITA } else { c });
+                }
+                changed.then_some(out)
+            }
+            Rule::Geminates => {
+                let mut chars = text.chars().peekable();
+                let mut out = String::with_capacity(text.len());
+                let mut changed = false;
+                while let Some(c) = chars.next() {
+                    if CONSONANTS.contains(&c) && chars.next_if_eq(&c).is_some() {
+                        changed = true;
+                    }
+                    out.push(c);
+                }
+                changed.then_some(out)
+            }
+            Rule::RetroflexLateral => text.contains('ḷ').then(|| text.replace('ḷ', "ḍ")),
+            Rule::Substitute(from, to) => {
+                (!from.is_empty() && text.contains(from.as_str())).then(|| text.replace(from, to))
+            }
+        }
+    }
+}
+
+/// The spelling rules used to key words across editions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Orthography {
+    pub rules: Vec<Rule>,
+}
+
+impl Default for Orthography {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                Rule::NasalToNiggahita,
+                Rule::Geminates,
+                Rule::RetroflexLateral,
+            ],
+        }
+    }
+}
+
+impl Orthography {
+    /// Edition-independent key of a normalized word. Returns the input
+    /// borrowed when no rule applies.
+    pub fn key<'w>(&self, word: &'w str) -> Cow<'w, str> {
+        let mut key = Cow::Borrowed(word);
+        for rule in &self.rules {
+            if let Some(changed) = rule.apply(&key) {
+                key = Cow::Owned(changed);
+            }
+        }
+        key
+    }
+
+    /// ASCII-folded keys of a search query, to compare with token keys.
+    /// A plain n or m before a stop may stand for any nasal, so "sangha"
+    /// is also keyed as saṃgha.
+    pub fn query_keys(&self, query: &str) -> Vec<String> {
+        let query = normalize_pali(query.trim());
+        let mut keys = vec![ascii_fold(&self.key(&query)).into_owned()];
+        let nasals: String = query
+            .chars()
+            .zip(query.chars().skip(1).map(Some).chain([None]))
+            .map(|(c, next)| match next {
+                Some(next) if matches!(c, 'n' | 'm') && STOPS.contains(&next) => NIGGAHITA,
+                _ => c,
+            })
+            .collect();
+        let key = ascii_fold(&self.key(&nasals)).into_owned();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+        keys.retain(|k| !k.is_empty());
+        keys
+    }
+
+    /// Sets `key` on a token and its sub-tokens from their normalized form.
+    pub fn apply(&self, token: &mut Token) {
+        token.key = match self.key(&token.normalized) {
+            Cow::Borrowed(_) => token.normalized.clone(),
+            Cow::Owned(key) => Cow::Owned(key),
+        };
+        for sub in &mut token.sub_tokens {
+            self.apply(sub);
+        }
+    }
+}
+
+// ---------------------------------------------------------
+// LETTER CLASSES
+// ---------------------------------------------------------
+
+const CONSONANTS: [char; 22] = [
+    'k', 'g', 'ṅ', 'c', 'j', 'ñ', 'ṭ', 'ḍ', 'ṇ', 't', 'd', 'n', 'p', 'b', 'm', 'y', 'r', 'l', 'v',
+    's', 'h', 'ḷ',
+];
+
+// Unaspirated stops; aspirates start with the same letter.
+const STOPS: [char; 10] = ['k', 'g', 'c', 'j', 'ṭ', 'ḍ', 't', 'd', 'p', 'b'];
+
+// Each nasal with the unaspirated stops of its class; aspirates start
+// with the same letter.
+const NASAL_CLASSES: [(char, [char; 2]); 5] = [
+    ('ṅ', ['k', 'g']),
+    ('ñ', ['c', 'j']),
+    ('ṇ', ['ṭ', 'ḍ']),
+    ('n', ['t', 'd']),
+    ('m', ['p', 'b']),
+];
+
+fn homorganic(nasal: char, next: char) -> bool {
+    NASAL_CLASSES
+        .iter()
+        .any(|&(n, stops)| n == nasal && stops.contains(&next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    #[test]
+    fn test_variant_keys() {
+        let orthography = Orthography::default();
+        let same = |a: &str, b: &str| orthography.key(a) == orthography.key(b);
+
+        assert!(same("saṃgha", "saṅgha"));
+        assert!(same("sammāsambuddho", "sammāsaṃbuddho"));
+        assert!(same("saṃkhāra", "saṅkhāra"));
+        assert!(same("sakkacca", "sakacca"));
+        assert!(same("āḷhaka", "āḍhaka"));
+        assert!(!same("saṃgha", "sagha"));
+        assert_eq!(orthography.key("bhagavā"), Cow::Borrowed("bhagavā"));
+
+        let custom = Orthography {
+            rules: vec![Rule::Substitute("v".to_string(), "b".to_string())],
+        };
+        assert_eq!(custom.key("vyākaraṇa"), "byākaraṇa");
+        assert_eq!(custom.key("saṅgha"), "saṅgha");
+    }
+
+    #[test]
+    fn test_variant_frequencies() {
+        let result = process_text("Saṅghaṃ saraṇaṃ gacchāmi. Saṃghaṃ saraṇaṃ gacchāmi.");
+        let tokens = &result.sentences[0].tokens;
+        assert_eq!(tokens[0].key, "saṃghaṃ");
+        assert!(tokens[0].same_word(&result.sentences[1].tokens[0]));
+        assert!(result.word_frequencies().contains(&("saṅghaṃ", 2)));
+    }
+
+    #[test]
+    fn test_plain_keyboard_query() {
+        let result = process_text("Saṅgha saṃgha santa sagha.");
+        let tokens = &result.sentences[0].tokens;
+        let keys = Orthography::default().query_keys("sangha");
+        assert!(tokens[0].matches_keys(&keys));
+        assert!(tokens[1].matches_keys(&keys));
+        assert!(!tokens[3].matches_keys(&keys));
+        assert!(tokens[0].matches_query("sangha"));
+        assert!(tokens[2].matches_query("santa"));
+
+        // Without the nasal rule "sangha" still finds saṅgha.
+        let geminates = Orthography {
+            rules: vec![Rule::Geminates],
+        };
+        assert!(tokens[0].matches_keys(&geminates.query_keys("sangha")));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::backend::marker::LocatorKind;
+use crate::backend::types::{SegResult, Sentence, TokenKind};
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Divisions of a canonical text, outermost first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// "namo tassa bhagavato arahato sammāsambuddhassa"
+    Invocation,
+    /// Piṭaka, nikāya or pāḷi volume, e.g. "Pārājikapāḷi".
+    Book,
+    /// Vagga, kaṇḍa or khandhaka, e.g. "(1) Paṭhamavaggo".
+    Vagga,
+    /// Sutta, jātaka or sikkhāpada, e.g. "1. Brahmajālasuttaṃ".
+    Sutta,
+    /// A numbered VRI paragraph.
+    Paragraph,
+}
+
+impl Level {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Level::Invocation => "Invocation",
+            Level::Book => "Book",
+            Level::Vagga => "Vagga",
+            Level::Sutta => "Sutta",
+            Level::Paragraph => "Paragraph",
+        }
+    }
+
+    /// Indentation in the outline; the invocation sits with books.
+    pub fn depth(&self) -> usize {
+        match self {
+            Level::Invocation | Level::Book => 0,
+            Level::Vagga => 1,
+            Level::Sutta => 2,
+            Level::Paragraph => 3,
+        }
+    }
+}
+
+/// One entry of the document outline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heading {
+    pub level: Level,
+    /// Number as written before the title, e.g. "1" from "(1) Paṭhamavaggo".
+    pub number: Option<String>,
+    /// Heading words; for paragraphs, the first few words.
+    pub title: String,
+    /// Document offset of the heading sentence.
+    pub offset: usize,
+    pub segment_id: Option<String>,
+}
+
+impl Heading {
+    /// e.g. "1. Verañjakaṇḍaṃ" or "¶ 12 Tena samayena…".
+    pub fn label(&self) -> String {
+        match (&self.number, self.level) {
+            (Some(n), Level::Paragraph) => format!("¶ {} {}", n, self.title),
+            (Some(n), _) => format!("{}. {}", n, self.title),
+            (None, _) => self.title.clone(),
+        }
+    }
+}
+
+/// Fills `outline` with the headings and numbered paragraphs of the prose,
+/// in document order. A heading is a short line without closing
+/// punctuation whose last word names a division ("-vaggo", "-suttaṃ"), or
+/// the namo tassa invocation.
+pub fn annotate(result: &mut SegResult) {
+    let mut outline = Vec::new();
+    for sentence in &result.sentences {
+        let offset = sentence.tokens.first().map_or(0, |t| t.offset);
+        if let Some((level, title)) = heading(sentence) {
+            outline.push(Heading {
+                level,
+                number: number(sentence),
+                title,
+                offset,
+                segment_id: sentence.segment_id.clone(),
+            });
+        } else if let Some(locator) = sentence
+            .locators
+            .iter()
+            .find(|l| l.kind == LocatorKind::Para)
+        {
+            let mut title = words(sentence)
+                .take(PARAGRAPH_TITLE_WORDS)
+                .collect::<Vec<_>>()
+                .join(" ");
+            if words(sentence).nth(PARAGRAPH_TITLE_WORDS).is_some() {
+                title.push('…');
+            }
+            outline.push(Heading {
+                level: Level::Paragraph,
+                number: Some(locator.value.clone()),
+                title,
+                offset,
+                segment_id: sentence.segment_id.clone(),
+            });
+        }
+    }
+    result.outline = outline;
+}
+
+/// True if the sentence is a heading or the namo tassa invocation.
+pub fn is_heading(sentence: &Sentence) -> bool {
+    heading(sentence).is_some()
+}
+
+// ---------------------------------------------------------
+// DETECTION
+// ---------------------------------------------------------
+
+const MAX_HEADING_WORDS: usize = 4;
+const PARAGRAPH_TITLE_WORDS: usize = 3;
+const CLOSING_PUNCTUATION: [char; 8] = ['.', '?', '!', ',', ';', '।', '॥', '။'];
+
+// Endings of the last heading word, checked in this order so that
+// "Sīlakkhandhavaggapāḷi" is a book and not a vagga.
+const DIVISIONS: [(&str, Level); 19] = [
+    ("pāḷi", Level::Book),
+    ("piṭakaṃ", Level::Book),
+    ("piṭake", Level::Book),
+    ("nikāyo", Level::Book),
+    ("nikāye", Level::Book),
+    ("saṃyuttaṃ", Level::Book),
+    ("nipāto", Level::Book),
+    ("vaggo", Level::Vagga),
+    ("vagga", Level::Vagga),
+    ("kaṇḍaṃ", Level::Vagga),
+    ("kaṇḍo", Level::Vagga),
+    ("khandhako", Level::Vagga),
+    ("paṇṇāsakaṃ", Level::Vagga),
+    ("suttaṃ", Level::Sutta),
+    ("suttanto", Level::Sutta),
+    ("sutta", Level::Sutta),
+    ("jātakaṃ", Level::Sutta),
+    ("sikkhāpadaṃ", Level::Sutta),
+    ("vatthu", Level::Sutta),
+];
+
+fn words<'s>(sentence: &'s Sentence) -> impl Iterator<Item = &'s str> {
+    sentence
+        .tokens
+        .iter()
+        .filter(|t| t.kind == TokenKind::Word)
+        .map(|t| t.text.as_ref())
+}
+
+fn heading(sentence: &Sentence) -> Option<(Level, String)> {
+    let normalized: Vec<&str> = sentence
+        .tokens
+        .iter()
+        .filter(|t| t.kind == TokenKind::Word)
+        .map(|t| t.normalized.as_ref())
+        .collect();
+    let title = words(sentence).collect::<Vec<_>>().join(" ");
+    if normalized.starts_with(&["namo", "tassa"]) {
+        return Some((Level::Invocation, title));
+    }
+
+    let text = sentence.text.trim();
+    if !sentence.paragraph_start
+        || text.ends_with(CLOSING_PUNCTUATION)
+        || normalized.is_empty()
+        || normalized.len() > MAX_HEADING_WORDS
+    {
+        return None;
+    }
+    let last = normalized.last()?;
+    DIVISIONS
+        .iter()
+        .find(|(ending, _)| last.ends_with(ending))
+        .map(|&(_, level)| (level, title))
+}
+
+// "1" from a leading "1." or "(1)".
+fn number(sentence: &Sentence) -> Option<String> {
+    if let Some(marker) = &sentence.list_marker {
+        return Some(marker.label.clone());
+    }
+    let first = sentence.tokens.first()?;
+    let locator = sentence
+        .locators
+        .iter()
+        .find(|l| l.offset == first.offset)?;
+    Some(locator.value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    #[test]
+    fn test_outline_levels() {
+        let text = "Namo tassa bhagavato arahato sammāsambuddhassa\n\nPārājikapāḷi\n\n1. Verañjakaṇḍaṃ\n\n1. Tena samayena buddho bhagavā verañjāyaṃ viharati.\n\n(1) Paṭhamavaggo\n\nBhikkhū sutaṃ vaggo ahosi.";
+        let mut result = process_text(text);
+        annotate(&mut result);
+        let outline: Vec<(Level, String)> = result
+            .outline
+            .iter()
+            .map(|h| (h.level, h.label()))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                (
+                    Level::Invocation,
+                    "Namo tassa bhagavato arahato sammāsambuddhassa".to_string()
+                ),
+                (Level::Book, "Pārājikapāḷi".to_string()),
+                (Level::Vagga, "1. Verañjakaṇḍaṃ".to_string()),
+                (Level::Paragraph, "¶ 1 Tena samayena buddho…".to_string()),
+                (Level::Vagga, "1. Paṭhamavaggo".to_string()),
+            ]
+        );
+        assert_eq!(&text[result.outline[2].offset..][..2], "1.");
+    }
+}
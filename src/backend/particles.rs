@@ -0,0 +1,74 @@
+use crate::backend::types::{Token, TokenKind};
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Indeclinable particles and enclitics, in normalized form.
+pub const PARTICLES: [&str; 24] = [
+    "api", "atha", "ca", "ce", "eva", "ha", "hi", "iti", "kho", "khalu", "kira", "nāma", "nu",
+    "pana", "pi", "su", "tāva", "ti", "tu", "udāhu", "va", "vā", "ve", "yeva",
+];
+
+pub fn is_particle(word: &str) -> bool {
+    PARTICLES.contains(&word)
+}
+
+/// Marks particles as `TokenKind::Particle`: sandhi parts whose base is a
+/// particle, and words that are particles or consist only of them (itipi).
+pub fn classify(tokens: &mut [Token]) {
+    for token in tokens.iter_mut().filter(|t| t.kind == TokenKind::Word) {
+        for sub in token.sub_tokens.iter_mut() {
+            if is_particle(&sub.normalized) {
+                sub.kind = TokenKind::Particle;
+            }
+        }
+        let all_parts = !token.sub_tokens.is_empty()
+            && token
+                .sub_tokens
+                .iter()
+                .all(|t| t.kind == TokenKind::Particle);
+        if is_particle(&token.normalized) || all_parts {
+            token.kind = TokenKind::Particle;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::{SegOptions, process_text_with};
+
+    #[test]
+    fn test_particles_and_enclitics() {
+        let text = "Taṃ kho pana bhavantaṃ ‘itipi so’va bhagavā dhammañca.";
+        let options = SegOptions {
+            sandhi: true,
+            ..Default::default()
+        };
+        let result = process_text_with(text, &options);
+        let tokens = &result.sentences[0].tokens;
+        let kind = |text: &str| tokens.iter().find(|t| t.text == text).unwrap().kind;
+
+        assert_eq!(kind("kho"), TokenKind::Particle);
+        assert_eq!(kind("pana"), TokenKind::Particle);
+        assert_eq!(kind("itipi"), TokenKind::Particle);
+        assert_eq!(kind("bhavantaṃ"), TokenKind::Word);
+
+        let so_va = tokens.iter().find(|t| t.text == "so’va").unwrap();
+        assert_eq!(so_va.kind, TokenKind::Word);
+        let parts: Vec<(&str, TokenKind)> = so_va
+            .sub_tokens
+            .iter()
+            .map(|t| (t.normalized.as_ref(), t.kind))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![("so", TokenKind::Word), ("eva", TokenKind::Particle)]
+        );
+
+        let dhamma = tokens.iter().find(|t| t.text == "dhammañca").unwrap();
+        assert_eq!(dhamma.sub_tokens[1].kind, TokenKind::Particle);
+        assert_eq!(result.word_frequencies().len(), 5);
+    }
+}
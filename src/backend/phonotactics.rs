@@ -0,0 +1,347 @@
+use crate::backend::normalize::normalize_pali;
+use crate::backend::prosody::{Indic, indic};
+use crate::backend::types::{Diagnostic, SegResult, Severity, TokenKind};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// A letter sequence that cannot occur in a Pali word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// "bhkk": an aspirate before a stop, or a run of consonants no
+    /// assimilation produces.
+    Cluster,
+    /// "ṃṃ"
+    DoubleNiggahita,
+    /// "saṃudda": within a word niggahīta is written m before a vowel.
+    VowelAfterNiggahita,
+    /// A word opening with niggahīta.
+    InitialNiggahita,
+    /// An Indic vowel sign or virama with no consonant to carry it.
+    DanglingSign,
+}
+
+impl Problem {
+    pub fn message(self) -> &'static str {
+        match self {
+            Problem::Cluster => "Impossible consonant cluster",
+            Problem::DoubleNiggahita => "Doubled niggahīta",
+            Problem::VowelAfterNiggahita => "Vowel after niggahīta inside a word",
+            Problem::InitialNiggahita => "Word begins with niggahīta",
+            Problem::DanglingSign => "Vowel sign or virama without a consonant",
+        }
+    }
+}
+
+/// Phonotactic problems in one word, with byte ranges in `word`. Roman
+/// words with letters outside the Pali alphabet are not checked, so
+/// English glosses pass.
+pub fn check(word: &str) -> Vec<(Range<usize>, Problem)> {
+    if word.chars().any(|c| indic(c).is_some()) {
+        check_indic(word)
+    } else {
+        check_roman(word)
+    }
+}
+
+/// Adds a warning to `diagnostics` for each problem in a word token.
+pub fn annotate(result: &mut SegResult) {
+    let mut found = Vec::new();
+    for token in result.segments().into_iter().flat_map(|s| &s.tokens) {
+        if !matches!(token.kind, TokenKind::Word | TokenKind::Particle) {
+            continue;
+        }
+        for (range, problem) in check(&token.text) {
+            // Diagnostics refer to the text as given.
+            let start = result.offset_map.to_original(token.offset + range.start);
+            let end = result.offset_map.to_original(token.offset + range.end);
+            found.push(Diagnostic {
+                offset: start,
+                len: end.saturating_sub(start).max(1),
+                severity: Severity::Warning,
+                message: format!(
+                    "{} \"{}\" in \"{}\"",
+                    problem.message(),
+                    &token.text[range],
+                    token.text
+                ),
+            });
+        }
+    }
+    result.diagnostics.extend(found);
+}
+
+// ---------------------------------------------------------
+// ROMAN
+// ---------------------------------------------------------
+
+const VOWELS: [char; 8] = ['a', 'ā', 'i', 'ī', 'u', 'ū', 'e', 'o'];
+const CONSONANTS: [char; 22] = [
+    'k', 'g', 'ṅ', 'c', 'j', 'ñ', 'ṭ', 'ḍ', 'ṇ', 't', 'd', 'n', 'p', 'b', 'm', 'y', 'r', 'l', 'v',
+    's', 'h', 'ḷ',
+];
+const NASALS: [char; 5] = ['ṅ', 'ñ', 'ṇ', 'n', 'm'];
+// Unaspirated stops that form one consonant with a following h.
+const STOPS: [char; 10] = ['k', 'g', 'c', 'j', 'ṭ', 'ḍ', 't', 'd', 'p', 'b'];
+// Consonants that may follow an aspirate, as in bhy, dhv.
+const AFTER_ASPIRATE: [char; 3] = ['y', 'r', 'v'];
+// Last members that allow a third consonant, as in indriya, brahma.
+const CLUSTER_ENDS: [char; 10] = ['y', 'r', 'v', 'l', 'h', 'ṅ', 'ñ', 'ṇ', 'n', 'm'];
+const MAX_CLUSTER: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Sound {
+    Vowel,
+    Niggahita,
+    Consonant { letter: char, aspirate: bool },
+    // Apostrophes and other non-letters inside the token.
+    Break,
+}
+
+fn check_roman(word: &str) -> Vec<(Range<usize>, Problem)> {
+    let Some(sounds) = roman_sounds(word) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+
+    for (i, (range, sound)) in sounds.iter().enumerate() {
+        if *sound != Sound::Niggahita {
+            continue;
+        }
+        let next = sounds.get(i + 1);
+        match next.map(|(_, s)| *s) {
+            Some(Sound::Niggahita) => {
+                out.push((range.start..next.unwrap().0.end, Problem::DoubleNiggahita))
+            }
+            Some(Sound::Vowel) => out.push((
+                range.start..next.unwrap().0.end,
+                Problem::VowelAfterNiggahita,
+            )),
+            _ if i == 0 => out.push((range.clone(), Problem::InitialNiggahita)),
+            _ => {}
+        }
+    }
+
+    // Maximal consonant runs
+    let mut i = 0;
+    while i < sounds.len() {
+        let run_end = (i..sounds.len())
+            .find(|&j| !matches!(sounds[j].1, Sound::Consonant { .. }))
+            .unwrap_or(sounds.len());
+        if run_end > i && !is_possible_cluster(&sounds[i..run_end]) {
+            out.push((
+                sounds[i].0.start..sounds[run_end - 1].0.end,
+                Problem::Cluster,
+            ));
+        }
+        i = run_end.max(i + 1);
+    }
+    out.sort_by_key(|(range, _)| range.start);
+    out
+}
+
+fn is_possible_cluster(run: &[(Range<usize>, Sound)]) -> bool {
+    let letters: Vec<(char, bool)> = run
+        .iter()
+        .filter_map(|(_, s)| match s {
+            Sound::Consonant { letter, aspirate } => Some((*letter, *aspirate)),
+            _ => None,
+        })
+        .collect();
+    if letters.len() > MAX_CLUSTER {
+        return false;
+    }
+    let aspirate_ok = letters
+        .windows(2)
+        .all(|pair| !pair[0].1 || AFTER_ASPIRATE.contains(&pair[1].0));
+    let triple_ok = letters.len() < MAX_CLUSTER
+        || NASALS.contains(&letters[0].0)
+        || CLUSTER_ENDS.contains(&letters[letters.len() - 1].0);
+    aspirate_ok && triple_ok
+}
+
+// Sounds of a Roman word, or `None` if it uses letters Pali lacks.
+fn roman_sounds(word: &str) -> Option<Vec<(Range<usize>, Sound)>> {
+    let mut sounds: Vec<(Range<usize>, Sound)> = Vec::new();
+    for (start, grapheme) in word.grapheme_indices(true) {
+        let range = start..start + grapheme.len();
+        let letter = normalize_pali(grapheme).chars().next()?;
+        let sound = if VOWELS.contains(&letter) {
+            Sound::Vowel
+        } else if letter == 'ṃ' {
+            Sound::Niggahita
+        } else if CONSONANTS.contains(&letter) {
+            // A stop and h make one aspirate.
+            if letter == 'h'
+                && let Some((
+                    prev,
+                    Sound::Consonant {
+                        letter: stop,
+                        aspirate,
+                    },
+                )) = sounds.last_mut()
+                && STOPS.contains(stop)
+                && !*aspirate
+            {
+                prev.end = range.end;
+                *aspirate = true;
+                continue;
+            }
+            Sound::Consonant {
+                letter,
+                aspirate: false,
+            }
+        } else if letter.is_alphabetic() {
+            return None;
+        } else {
+            Sound::Break
+        };
+        sounds.push((range, sound));
+    }
+    Some(sounds)
+}
+
+// ---------------------------------------------------------
+// INDIC SCRIPTS
+// ---------------------------------------------------------
+
+fn check_indic(word: &str) -> Vec<(Range<usize>, Problem)> {
+    let chars: Vec<(Range<usize>, Option<Indic>)> = word
+        .char_indices()
+        .map(|(i, c)| (i..i + c.len_utf8(), indic(c)))
+        .collect();
+    let mut out = Vec::new();
+    // Last class before the current letter, ignoring marks such as tone
+    // or dot below.
+    let mut prev: Option<Indic> = None;
+    let mut prev_range: Option<Range<usize>> = None;
+    // Consonants joined by viramas so far.
+    let mut stack = 0;
+    let mut stack_start = 0;
+
+    for (range, class) in &chars {
+        let Some(class) = *class else {
+            prev = None;
+            prev_range = None;
+            stack = 0;
+            continue;
+        };
+        let span = || {
+            prev_range
+                .clone()
+                .map_or(range.clone(), |p| p.start..range.end)
+        };
+        // Myanmar kinzi stacks a consonant after an asat: င်္ဂ
+        let kinzi = &word[range.clone()] == "\u{1039}"
+            && prev_range
+                .as_ref()
+                .is_some_and(|p| &word[p.clone()] == "\u{103A}");
+        match class {
+            Indic::Mark => continue,
+            Indic::Virama if kinzi => continue,
+            Indic::Anusvara => match prev {
+                None => out.push((range.clone(), Problem::InitialNiggahita)),
+                Some(Indic::Anusvara) => out.push((span(), Problem::DoubleNiggahita)),
+                _ => {}
+            },
+            Indic::Vowel(_) | Indic::Sign(_) if prev == Some(Indic::Anusvara) => {
+                out.push((span(), Problem::VowelAfterNiggahita))
+            }
+            Indic::Sign(_) | Indic::Virama
+                if !matches!(
+                    prev,
+                    Some(Indic::Consonant | Indic::Medial | Indic::Sign(_) | Indic::Vowel(_))
+                ) =>
+            {
+                out.push((span(), Problem::DanglingSign))
+            }
+            Indic::Consonant => {
+                if prev != Some(Indic::Virama) {
+                    stack = 0;
+                    stack_start = range.start;
+                }
+                stack += 1;
+                if stack == MAX_CLUSTER + 1 {
+                    out.push((stack_start..range.end, Problem::Cluster));
+                }
+            }
+            _ => {}
+        }
+        prev = Some(class);
+        prev_range = Some(range.clone());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    fn problems(word: &str) -> Vec<(&str, Problem)> {
+        check(word)
+            .into_iter()
+            .map(|(range, problem)| (&word[range], problem))
+            .collect()
+    }
+
+    #[test]
+    fn test_roman_problems() {
+        for word in [
+            "sammāsambuddhassa",
+            "indriya",
+            "brahmacariyaṃ",
+            "abbhuggato",
+            "gantvā",
+            "Vuttañhetaṃ",
+            "sacchikatvā",
+            "the",
+        ] {
+            assert!(check(word).is_empty(), "{}", word);
+        }
+        assert_eq!(problems("abhkkanta"), vec![("bhkk", Problem::Cluster)]);
+        assert_eq!(problems("saṃṃgha"), vec![("ṃṃ", Problem::DoubleNiggahita)]);
+        assert_eq!(
+            problems("saṃudda"),
+            vec![("ṃu", Problem::VowelAfterNiggahita)]
+        );
+        assert_eq!(problems("ṃsa"), vec![("ṃ", Problem::InitialNiggahita)]);
+    }
+
+    #[test]
+    fn test_indic_problems() {
+        // dhammaṃ, saṅgho in Devanagari and Myanmar; Myanmar saṅgaha
+        for word in ["धम्मं", "सङ्घो", "ဓမ္မံ", "သံဃော", "သင်္ဂြိုဟ်"]
+        {
+            assert!(check(word).is_empty(), "{}", word);
+        }
+        assert_eq!(
+            problems("धम्मंं"),
+            vec![("\u{0902}\u{0902}", Problem::DoubleNiggahita)]
+        );
+        assert_eq!(
+            problems("संउ"),
+            vec![("\u{0902}उ", Problem::VowelAfterNiggahita)]
+        );
+        assert_eq!(
+            problems("\u{093E}क"),
+            vec![("\u{093E}", Problem::DanglingSign)]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let text = "Bhagavā abhkkanta viharati.";
+        let mut result = process_text(text);
+        annotate(&mut result);
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(
+            &text[diagnostic.offset..diagnostic.offset + diagnostic.len],
+            "bhkk"
+        );
+    }
+}
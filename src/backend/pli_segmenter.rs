@@ -1,6 +1,21 @@
-use crate::backend::types::{SegResult, Sentence, Token, TokenKind};
+use crate::backend::elision;
+use crate::backend::lexicon::Lexicon;
+use crate::backend::list_marker::ListGrammar;
+use crate::backend::marker;
+use crate::backend::myanmar;
+use crate::backend::normalize::normalize_pali;
+use crate::backend::numeral;
+use crate::backend::orthography::Orthography;
+use crate::backend::particles;
+use crate::backend::prosody;
+use crate::backend::reference;
+use crate::backend::sandhi;
+use crate::backend::types::{SegResult, Sentence, Stanza, Token, TokenKind};
+use crate::backend::verse;
 use std::borrow::Cow;
 use std::iter::Peekable;
+use std::ops::Range;
+use std::sync::Arc;
 use unicode_general_category::{GeneralCategory, get_general_category};
 use unicode_segmentation::{USentenceBoundIndices, UnicodeSegmentation};
 
@@ -8,19 +23,128 @@ use unicode_segmentation::{USentenceBoundIndices, UnicodeSegmentation};
 // PUBLIC API
 // ---------------------------------------------------------
 
+/// How Myanmar-script words, which have no internal spaces, are split.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum SyllableMode {
+    /// Keep merged words as they are.
+    #[default]
+    Off,
+    /// Attach `TokenKind::Syllable` sub-tokens to each Myanmar word.
+    SubTokens,
+    /// Replace each Myanmar word with its syllables as top-level tokens.
+    Syllables,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegOptions {
+    pub syllables: SyllableMode,
+    /// Join and split sandhi junctions such as hotī’ti = hoti + iti.
+    pub sandhi: bool,
+    /// Fill peyyāla markers from earlier occurrences of the formula.
+    pub expand_elisions: bool,
+    /// Forms accepted as list markers such as "(ka)" or "၁။".
+    pub list_markers: ListGrammar,
+    /// Spelling variants levelled in each token's `key`.
+    pub orthography: Orthography,
+    /// Headwords that sandhi splitting leaves whole; shared, since options
+    /// are cloned for each run of prose.
+    pub lexicon: Arc<Lexicon>,
+}
+
+impl Default for SegOptions {
+    fn default() -> Self {
+        Self {
+            syllables: SyllableMode::default(),
+            sandhi: false,
+            expand_elisions: false,
+            list_markers: ListGrammar::default(),
+            orthography: Orthography::default(),
+            lexicon: Arc::new(Lexicon::bundled().clone()),
+        }
+    }
+}
+
 pub fn process_text<'a>(text: &'a str) -> SegResult<'a> {
+    process_text_with(text, &SegOptions::default())
+}
+
+pub fn process_text_with<'a>(text: &'a str, options: &SegOptions) -> SegResult<'a> {
     if text.trim().is_empty() {
         return SegResult::default();
     }
 
-    let sentences = SentenceIterator::new(text).collect();
+    // Verse blocks are cut out and segmented by pāda; prose in between
+    // goes through the sentence iterator.
+    let mut next_id = 1;
+    let mut sentences = Vec::new();
+    let mut stanzas = Vec::new();
+    let mut prose_start = 0;
+    for span in verse::find_stanzas(text) {
+        sentences.extend(prose_sentences(
+            text,
+            prose_start..span.range.start,
+            options,
+            &mut next_id,
+        ));
+        let padas = span
+            .padas
+            .iter()
+            .map(|r| build_sentence(&text[r.clone()], r.start, false, options, &mut next_id))
+            .collect();
+        let pada_texts: Vec<&str> = span.padas.iter().map(|r| &text[r.clone()]).collect();
+        stanzas.push(Stanza {
+            offset: span.range.start,
+            text: Cow::Borrowed(&text[span.range.clone()]),
+            number: span.number,
+            padas,
+            metres: prosody::scan(&pada_texts),
+        });
+        prose_start = span.range.end;
+    }
+    sentences.extend(prose_sentences(
+        text,
+        prose_start..text.len(),
+        options,
+        &mut next_id,
+    ));
+
+    let mut result = SegResult {
+        sentences,
+        stanzas,
+        ..Default::default()
+    };
+    if options.expand_elisions {
+        elision::expand(&mut result);
+    }
+    result
+}
 
-    SegResult { sentences }
+fn prose_sentences<'a>(
+    text: &'a str,
+    range: Range<usize>,
+    options: &SegOptions,
+    next_id: &mut usize,
+) -> Vec<Sentence<'a>> {
+    let mut iter =
+        SentenceIterator::new(&text[range.clone()], range.start, *next_id, options.clone());
+    let sentences = iter.by_ref().collect();
+    *next_id = iter.token_id_counter;
+    sentences
+}
+
+/// Runs the pre-processing stages before segmenting. Token offsets refer to
+/// the prepared text; `offset_map` maps them back to `text`.
+pub fn process_document(text: &str, options: &SegOptions) -> SegResult<'static> {
+    let prepared = myanmar::preprocess(text);
+    let mut result = process_text_with(&prepared.text, options).to_owned_data();
+    result.diagnostics = prepared.diagnostics;
+    result.offset_map = prepared.offset_map;
+    result
 }
 
 fn classify_token(text: &str) -> TokenKind {
-    // Optimization: Check bytes for number
-    if !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit()) {
+    // Digits of any script, possibly dotted ("1.157", "၁၅၇")
+    if numeral::dotted_number(text) == Some(text.len()) {
         return TokenKind::Number;
     }
 
@@ -104,48 +228,30 @@ impl BalanceTracker {
 struct SentenceIterator<'a> {
     iter: Peekable<USentenceBoundIndices<'a>>,
     text_source: &'a str,
+    // Offset of `text_source` in the document.
+    base_offset: usize,
     tracker: BalanceTracker,
     token_id_counter: usize,
+    options: SegOptions,
 }
 
 impl<'a> SentenceIterator<'a> {
-    fn new(text: &'a str) -> Self {
+    fn new(text: &'a str, base_offset: usize, first_id: usize, options: SegOptions) -> Self {
         Self {
             iter: text.split_sentence_bound_indices().peekable(),
             text_source: text,
+            base_offset,
             tracker: BalanceTracker::new(),
-            token_id_counter: 1,
+            token_id_counter: first_id,
+            options,
         }
     }
 }
 
-// Heuristic: Is this segment a list marker? e.g., "1.", "(1)", "၁။"
-fn is_list_marker(text: &str) -> bool {
-    let trimmed = text.trim();
-    // List markers are usually short
-    if trimmed.len() > 10 {
-        return false;
-    }
-
-    let mut chars = trimmed.chars();
-    let first = chars.next();
-
-    // Check if starts with digit or open paren
-    let starts_valid = first.is_some_and(|c| c.is_numeric() || c == '(');
-    if !starts_valid {
-        return false;
-    }
-
-    // Check ending: specific Myanmar punctuation or dot
-    // \u{104A} is '၊', \u{104B} is '။'
-    let last = trimmed.chars().last().unwrap_or(' ');
-    matches!(last, '.' | '\u{104A}' | '\u{104B}')
-}
-
-fn is_abbreviation(text: &str) -> bool {
+fn is_abbreviation(text: &str, list_markers: &ListGrammar) -> bool {
     // Simple heuristic: short text ending in dot, but not a list marker
     let trimmed = text.trim();
-    trimmed.len() < 5 && trimmed.ends_with('.') && !is_list_marker(text)
+    trimmed.len() < 5 && trimmed.ends_with('.') && list_markers.parse(text).is_none()
 }
 
 impl<'a> Iterator for SentenceIterator<'a> {
@@ -164,6 +270,10 @@ impl<'a> Iterator for SentenceIterator<'a> {
             }
         };
 
+        // Only blank space since the last line break, or the start
+        let before = self.text_source[..start_offset].trim_end_matches([' ', '\t']);
+        let paragraph_start = before.is_empty() || before.ends_with('\n');
+
         // We will accumulate parts if they need merging (unbalanced or list markers)
         let mut current_end = start_offset + first_part.len();
         self.tracker.update(first_part);
@@ -179,12 +289,32 @@ impl<'a> Iterator for SentenceIterator<'a> {
             // Usually list marker is the *start* of the sentence.
             // So we check the text from start_offset to current_end.
             let current_text = &self.text_source[start_offset..current_end];
-            let looks_like_list = is_list_marker(current_text);
+            let list_markers = &self.options.list_markers;
+            let looks_like_list = list_markers.parse(current_text).is_some();
 
             // 3. Current part looks like an abbreviation
-            let looks_like_abbr = is_abbreviation(current_text);
-
-            if is_merging || looks_like_list || looks_like_abbr {
+            let looks_like_abbr = is_abbreviation(current_text, list_markers);
+
+            // 4. Current part ends in a peyyāla marker, which elides text
+            // rather than ending the sentence
+            let after_elision = elision::ends_with_marker(current_text);
+
+            // 5. Current part ends in a siglum and the next part opens
+            // with its locator
+            let after_siglum = reference::ends_with_siglum(current_text)
+                && reference::starts_with_locator(peek_part);
+
+            // 6. Current part is only page or paragraph markers, which
+            // belong to the sentence that follows
+            let only_markers = marker::is_marker_only(current_text, paragraph_start);
+
+            if is_merging
+                || looks_like_list
+                || looks_like_abbr
+                || after_elision
+                || after_siglum
+                || only_markers
+            {
                 // Consume the peeked item
                 self.tracker.update(peek_part);
                 current_end = *peek_offset + peek_part.len();
@@ -196,13 +326,104 @@ impl<'a> Iterator for SentenceIterator<'a> {
 
         let full_text = &self.text_source[start_offset..current_end];
 
-        // Tokenize this sentence
-        let tokens = tokenize_sentence(full_text, start_offset, &mut self.token_id_counter);
+        Some(build_sentence(
+            full_text,
+            self.base_offset + start_offset,
+            paragraph_start,
+            &self.options,
+            &mut self.token_id_counter,
+        ))
+    }
+}
 
-        Some(Sentence {
-            text: Cow::Borrowed(full_text),
-            tokens,
-        })
+// Tokenizes one sentence or pāda starting at `offset` in the document and
+// runs the per-token stages. `paragraph_start` allows a leading VRI
+// paragraph number.
+fn build_sentence<'a>(
+    full_text: &'a str,
+    offset: usize,
+    paragraph_start: bool,
+    options: &SegOptions,
+    next_id: &mut usize,
+) -> Sentence<'a> {
+    // Tokenize this sentence
+    let first_id = *next_id;
+    let mut tokens = tokenize_sentence(full_text, offset, next_id);
+
+    for token in &mut tokens {
+        normalize_token(token);
+        if token.kind == TokenKind::Number {
+            token.value = numeral::parse(&token.text);
+        }
+    }
+    tokens = elision::join_markers(tokens, full_text, offset);
+    let (joined, locators) = marker::join(tokens, full_text, offset, paragraph_start);
+    tokens = joined;
+    tokens = reference::join(tokens, full_text, offset);
+
+    if options.sandhi {
+        tokens = sandhi::join_quotatives(tokens, full_text, offset);
+        for token in tokens.iter_mut().filter(|t| t.kind == TokenKind::Word) {
+            token.sub_tokens = sandhi::split_token(token, &options.lexicon);
+        }
+    }
+    particles::classify(&mut tokens);
+    numeral::tag_words(&mut tokens);
+
+    match options.syllables {
+        SyllableMode::Off => {}
+        SyllableMode::SubTokens => {
+            for token in tokens.iter_mut().filter(|t| is_myanmar_word(t)) {
+                token.sub_tokens = split_syllables(token);
+            }
+        }
+        SyllableMode::Syllables => {
+            tokens = tokens
+                .into_iter()
+                .flat_map(|mut token| {
+                    if !is_myanmar_word(&token) {
+                        return vec![token];
+                    }
+                    let syllables = split_syllables(&token);
+                    if syllables.is_empty() {
+                        token.kind = TokenKind::Syllable;
+                        vec![token]
+                    } else {
+                        syllables
+                    }
+                })
+                .collect();
+        }
+    }
+
+    // Spelling keys after every stage that builds tokens
+    for token in &mut tokens {
+        options.orthography.apply(token);
+    }
+
+    // Stages above may join or split tokens, so ids are assigned last.
+    *next_id = first_id;
+    for token in &mut tokens {
+        token.id = *next_id;
+        for sub in &mut token.sub_tokens {
+            sub.id = *next_id;
+        }
+        *next_id += 1;
+    }
+
+    // A VRI paragraph number is a locator, not a list item.
+    let list_marker = match tokens.first() {
+        Some(t) if t.kind == TokenKind::ParaMarker => None,
+        _ => options.list_markers.leading(full_text),
+    };
+
+    Sentence {
+        text: Cow::Borrowed(full_text),
+        tokens,
+        locators,
+        list_marker,
+        paragraph_start,
+        segment_id: None,
     }
 }
 
@@ -210,6 +431,34 @@ impl<'a> Iterator for SentenceIterator<'a> {
 // TOKENIZER LOGIC
 // ---------------------------------------------------------
 
+fn normalize_token(token: &mut Token<'_>) {
+    token.normalized = match &token.text {
+        Cow::Borrowed(t) => normalize_pali(t),
+        Cow::Owned(t) => Cow::Owned(normalize_pali(t).into_owned()),
+    };
+}
+
+fn is_myanmar_word(token: &Token) -> bool {
+    token.kind == TokenKind::Word && token.text.chars().any(myanmar::is_myanmar)
+}
+
+// Syllable sub-tokens for a word; empty if it is a single syllable.
+fn split_syllables<'a>(token: &Token<'a>) -> Vec<Token<'a>> {
+    let ranges = myanmar::syllables(&token.text);
+    if ranges.len() < 2 {
+        return Vec::new();
+    }
+    ranges
+        .into_iter()
+        .map(|r| {
+            let offset = token.offset + r.start;
+            let mut sub = Token::new(token.id, offset, token.sub_text(r), TokenKind::Syllable);
+            normalize_token(&mut sub);
+            sub
+        })
+        .collect()
+}
+
 fn tokenize_sentence<'a>(
     text: &'a str,
     base_offset: usize,
@@ -242,12 +491,12 @@ fn tokenize_sentence<'a>(
                 // Was merging words, now brackets -> emit previous, start merge
                 let start = pending_start.unwrap();
                 let len = pending_end - start;
-                tokens.push(Token {
-                    id: *id_counter,
-                    offset: base_offset + start,
-                    text: Cow::Borrowed(&text[start..start + len]),
-                    kind: pending_kind.unwrap(),
-                });
+                tokens.push(Token::new(
+                    *id_counter,
+                    base_offset + start,
+                    Cow::Borrowed(&text[start..start + len]),
+                    pending_kind.unwrap(),
+                ));
                 *id_counter += 1;
 
                 pending_start = Some(local_offset);
@@ -259,12 +508,12 @@ fn tokenize_sentence<'a>(
                 // Finished merging
                 let start = pending_start.unwrap();
                 let len = pending_end - start;
-                tokens.push(Token {
-                    id: *id_counter,
-                    offset: base_offset + start,
-                    text: Cow::Borrowed(&text[start..start + len]),
-                    kind: TokenKind::Merged,
-                });
+                tokens.push(Token::new(
+                    *id_counter,
+                    base_offset + start,
+                    Cow::Borrowed(&text[start..start + len]),
+                    TokenKind::Merged,
+                ));
                 *id_counter += 1;
                 pending_start = None;
                 pending_kind = None;
@@ -275,12 +524,12 @@ fn tokenize_sentence<'a>(
                 if let Some(start) = pending_start {
                     // Emit pending
                     let len = pending_end - start;
-                    tokens.push(Token {
-                        id: *id_counter,
-                        offset: base_offset + start,
-                        text: Cow::Borrowed(&text[start..start + len]),
-                        kind: pending_kind.unwrap(),
-                    });
+                    tokens.push(Token::new(
+                        *id_counter,
+                        base_offset + start,
+                        Cow::Borrowed(&text[start..start + len]),
+                        pending_kind.unwrap(),
+                    ));
                     *id_counter += 1;
                     pending_start = None;
                     pending_kind = None;
@@ -298,12 +547,12 @@ fn tokenize_sentence<'a>(
                     // Emit pending
                     let start = pending_start.unwrap();
                     let len = pending_end - start;
-                    tokens.push(Token {
-                        id: *id_counter,
-                        offset: base_offset + start,
-                        text: Cow::Borrowed(&text[start..start + len]),
-                        kind: pk,
-                    });
+                    tokens.push(Token::new(
+                        *id_counter,
+                        base_offset + start,
+                        Cow::Borrowed(&text[start..start + len]),
+                        pk,
+                    ));
                     *id_counter += 1;
 
                     // Start new
@@ -323,12 +572,12 @@ fn tokenize_sentence<'a>(
     // Emit remaining
     if let Some(start) = pending_start {
         let len = pending_end - start;
-        tokens.push(Token {
-            id: *id_counter,
-            offset: base_offset + start,
-            text: Cow::Borrowed(&text[start..start + len]),
-            kind: pending_kind.unwrap(),
-        });
+        tokens.push(Token::new(
+            *id_counter,
+            base_offset + start,
+            Cow::Borrowed(&text[start..start + len]),
+            pending_kind.unwrap(),
+        ));
         *id_counter += 1;
     }
 
@@ -338,6 +587,7 @@ fn tokenize_sentence<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::prosody::Metre;
 
     #[test]
     fn test_segmentation_logic() {
@@ -448,4 +698,74 @@ mod tests {
             result.sentences.len()
         );
     }
+
+    #[test]
+    fn test_normalized_word_frequencies() {
+        let text = "Saṃ saṁ saŋ sam\u{0323} bhikkhu.";
+        let result = process_text(text);
+        let tokens = &result.sentences[0].tokens;
+
+        assert_eq!(tokens[0].text, "Saṃ");
+        assert_eq!(tokens[0].normalized, "saṃ");
+        assert!(tokens[0].same_word(&tokens[3]));
+        assert_eq!(result.word_frequencies(), vec![("saṃ", 4), ("bhikkhu", 1)]);
+    }
+
+    #[test]
+    fn test_burmese_syllable_modes() {
+        let text = "တေန သမယေန ဗုဒ္ဓေါ ဘဂဝါ";
+
+        let options = SegOptions {
+            syllables: SyllableMode::SubTokens,
+            ..Default::default()
+        };
+        let result = process_text_with(text, &options);
+        let tokens = &result.sentences[0].tokens;
+        assert_eq!(tokens.len(), 4);
+        let syllables: Vec<&str> = tokens[1]
+            .sub_tokens
+            .iter()
+            .map(|t| t.text.as_ref())
+            .collect();
+        assert_eq!(syllables, vec!["သ", "မ", "ယေ", "န"]);
+        assert_eq!(tokens[1].sub_tokens[1].offset, tokens[1].offset + "သ".len());
+
+        let options = SegOptions {
+            syllables: SyllableMode::Syllables,
+            ..Default::default()
+        };
+        let result = process_text_with(text, &options);
+        let tokens = &result.sentences[0].tokens;
+        assert!(tokens.iter().all(|t| t.kind == TokenKind::Syllable));
+        assert_eq!(tokens.len(), 2 + 4 + 1 + 3);
+        let ids: Vec<usize> = tokens.iter().map(|t| t.id).collect();
+        assert_eq!(ids, (1..=10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_verse_segmentation() {
+        let text = "Evaṃ me sutaṃ.\n\n\
+                    Manopubbaṅgamā dhammā,\n\
+                    manoseṭṭhā manomayā;\n\
+                    Manasā ce paduṭṭhena,\n\
+                    bhāsati vā karoti vā. (1)\n\n\
+                    Iti vuttaṃ.";
+        let result = process_text(text);
+
+        let prose: Vec<&str> = result.sentences.iter().map(|s| s.text.trim()).collect();
+        assert_eq!(prose, vec!["Evaṃ me sutaṃ.", "Iti vuttaṃ."]);
+        assert_eq!(result.stanzas.len(), 1);
+        assert_eq!(result.stanzas[0].padas.len(), 4);
+        assert_eq!(result.stanzas[0].padas[1].tokens[0].text, "manoseṭṭhā");
+        assert_eq!(result.stanzas[0].metres.first(), Some(&Metre::Siloka));
+
+        // Ids run on in document order across prose and verse.
+        let ids: Vec<usize> = result
+            .segments()
+            .iter()
+            .flat_map(|s| &s.tokens)
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, (1..=ids.len()).collect::<Vec<_>>());
+    }
 }
@@ -0,0 +1,457 @@
+use crate::backend::normalize::normalize_pali;
+use std::ops::Range;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weight {
+    /// Heavy: long vowel, niggahīta, or closed by a consonant.
+    Garu,
+    /// Light: short open syllable.
+    Lahu,
+}
+
+impl Weight {
+    pub fn symbol(self) -> char {
+        match self {
+            Weight::Garu => '–',
+            Weight::Lahu => '⏑',
+        }
+    }
+
+    /// Length in mattā (morae).
+    pub fn morae(self) -> usize {
+        match self {
+            Weight::Garu => 2,
+            Weight::Lahu => 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Syllable {
+    /// Byte range in the syllabified text.
+    pub range: Range<usize>,
+    pub weight: Weight,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metre {
+    Siloka,
+    Tutthubha,
+    Jagati,
+    Vetaliya,
+    Opacchandasaka,
+}
+
+impl Metre {
+    pub fn label(self) -> &'static str {
+        match self {
+            Metre::Siloka => "siloka",
+            Metre::Tutthubha => "tuṭṭhubha",
+            Metre::Jagati => "jagatī",
+            Metre::Vetaliya => "vetālīya",
+            Metre::Opacchandasaka => "opacchandasaka",
+        }
+    }
+}
+
+/// Splits text into syllables with their weights. Roman, Devanagari,
+/// Sinhala, Myanmar, Thai and Khmer script are read; other characters
+/// separate words.
+/// A word-final syllable is heavy before a word starting with a consonant
+/// cluster, so pass a whole pāda to scan it.
+pub fn syllabify(text: &str) -> Vec<Syllable> {
+    let units = units(text);
+    let mut syllables = Vec::new();
+
+    let words: Vec<&[Unit]> = units
+        .split(|u| u.class == Class::Break)
+        .filter(|w| w.iter().any(|u| u.is_vowel()))
+        .collect();
+    for (w, word) in words.iter().enumerate() {
+        let next_onset = words.get(w + 1).map_or(0, |next| {
+            next.iter()
+                .take_while(|u| u.class == Class::Consonant)
+                .count()
+        });
+        let vowels: Vec<usize> = (0..word.len()).filter(|&i| word[i].is_vowel()).collect();
+
+        let mut start = 0;
+        for (k, &v) in vowels.iter().enumerate() {
+            // Consonants before the next vowel: all but the last close this
+            // syllable; word-final ones all do.
+            let end = match vowels.get(k + 1) {
+                Some(&next) => {
+                    let last_consonant = (v + 1..next)
+                        .rev()
+                        .find(|&i| word[i].class == Class::Consonant);
+                    last_consonant.unwrap_or(next)
+                }
+                None => word.len(),
+            };
+            let closed = end > v + 1;
+            let word_final = k + 1 == vowels.len();
+            let heavy =
+                word[v].class == Class::LongVowel || closed || (word_final && next_onset >= 2);
+
+            syllables.push(Syllable {
+                range: word[start].range.start..word[end - 1].range.end,
+                weight: if heavy { Weight::Garu } else { Weight::Lahu },
+            });
+            start = end;
+        }
+    }
+    syllables
+}
+
+/// Weight pattern as – and ⏑ symbols.
+pub fn pattern(syllables: &[Syllable]) -> String {
+    syllables.iter().map(|s| s.weight.symbol()).collect()
+}
+
+/// Metres the pādas fit, best first. Empty if none fits well.
+pub fn scan(padas: &[&str]) -> Vec<Metre> {
+    let weights: Vec<Vec<Weight>> = padas
+        .iter()
+        .map(|p| syllabify(p).into_iter().map(|s| s.weight).collect())
+        .collect();
+    if weights.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(Metre, f32)> = [
+        Metre::Siloka,
+        Metre::Tutthubha,
+        Metre::Jagati,
+        Metre::Vetaliya,
+        Metre::Opacchandasaka,
+    ]
+    .into_iter()
+    .map(|m| (m, score(m, &weights)))
+    .filter(|&(_, s)| s >= MIN_SCORE)
+    .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().map(|(m, _)| m).collect()
+}
+
+// ---------------------------------------------------------
+// METRES
+// ---------------------------------------------------------
+
+const MIN_SCORE: f32 = 0.75;
+
+// Share of pādas matching the metre's length, averaged with the share
+// matching its cadence.
+fn score(metre: Metre, padas: &[Vec<Weight>]) -> f32 {
+    use Weight::{Garu as G, Lahu as L};
+    let fraction = |hits: usize, of: usize| {
+        if of == 0 {
+            0.0
+        } else {
+            hits as f32 / of as f32
+        }
+    };
+    let has = |pada: &[Weight], at: usize, expected: &[Weight]| {
+        pada.get(at..at + expected.len()) == Some(expected)
+    };
+
+    match metre {
+        Metre::Siloka => {
+            // Eight syllables; even pādas have ⏑–⏑ in positions 5–7.
+            let even: Vec<&Vec<Weight>> = padas.iter().skip(1).step_by(2).collect();
+            let length = fraction(padas.iter().filter(|p| p.len() == 8).count(), padas.len());
+            let cadence = fraction(
+                even.iter().filter(|p| has(p, 4, &[L, G, L])).count(),
+                even.len(),
+            );
+            (length + cadence) / 2.0
+        }
+        Metre::Tutthubha | Metre::Jagati => {
+            // Eleven or twelve syllables ending –⏑–x or –⏑–⏑x.
+            let (len, at) = if metre == Metre::Tutthubha {
+                (11, 7)
+            } else {
+                (12, 7)
+            };
+            let length = fraction(padas.iter().filter(|p| p.len() == len).count(), padas.len());
+            let cadence = fraction(
+                padas.iter().filter(|p| has(p, at, &[G, L, G])).count(),
+                padas.len(),
+            );
+            (length + cadence) / 2.0
+        }
+        Metre::Vetaliya | Metre::Opacchandasaka => {
+            // Counted in mattā: odd pādas 14 or 16, even 16 or 18.
+            let base = if metre == Metre::Vetaliya { 14 } else { 16 };
+            let hits = padas
+                .iter()
+                .enumerate()
+                .filter(|(i, p)| p.iter().map(|w| w.morae()).sum::<usize>() == base + 2 * (i % 2))
+                .count();
+            fraction(hits, padas.len())
+        }
+    }
+}
+
+// ---------------------------------------------------------
+// PHONEME UNITS
+// ---------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Class {
+    Consonant,
+    ShortVowel,
+    LongVowel,
+    Niggahita,
+    Break,
+}
+
+#[derive(Clone, Debug)]
+struct Unit {
+    class: Class,
+    range: Range<usize>,
+}
+
+impl Unit {
+    fn is_vowel(&self) -> bool {
+        matches!(self.class, Class::ShortVowel | Class::LongVowel)
+    }
+}
+
+/// Letter classes in the Indic scripts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Indic {
+    Consonant,
+    Medial,
+    Vowel(bool),
+    Sign(bool),
+    // Thai e and o, written before their consonant.
+    Leading,
+    Virama,
+    Anusvara,
+    Mark,
+}
+
+// Unaspirated stops that form one consonant with a following h.
+const STOPS: [char; 10] = ['k', 'g', 'c', 'j', 'ṭ', 'ḍ', 't', 'd', 'p', 'b'];
+
+/// Class of a Devanagari, Sinhala, Myanmar, Thai or Khmer letter. The
+/// bool is vowel length; e and o count as long.
+pub fn indic(c: char) -> Option<Indic> {
+    use Indic::*;
+    Some(match c {
+        '\u{0915}'..='\u{0939}' | '\u{0D9A}'..='\u{0DC6}' | '\u{1000}'..='\u{1021}' => Consonant,
+        '\u{0E01}'..='\u{0E2E}' | '\u{1780}'..='\u{17A2}' => Consonant,
+        '\u{103B}'..='\u{103E}' => Medial,
+        '\u{0905}' | '\u{0907}' | '\u{0909}' | '\u{090B}' => Vowel(false),
+        '\u{0D85}' | '\u{0D89}' | '\u{0D8B}' => Vowel(false),
+        '\u{1023}' | '\u{1025}' => Vowel(false),
+        '\u{0906}' | '\u{0908}' | '\u{090A}' | '\u{090F}' | '\u{0910}' | '\u{0913}'
+        | '\u{0914}' => Vowel(true),
+        '\u{0D86}' | '\u{0D8A}' | '\u{0D8C}' | '\u{0D91}' | '\u{0D92}' | '\u{0D94}'
+        | '\u{0D95}' => Vowel(true),
+        '\u{1024}' | '\u{1026}' | '\u{1027}' | '\u{1029}' | '\u{102A}' => Vowel(true),
+        '\u{17A5}' | '\u{17A7}' => Vowel(false),
+        '\u{17A6}' | '\u{17A8}'..='\u{17B3}' => Vowel(true),
+        '\u{093F}' | '\u{0941}' | '\u{0943}' => Sign(false),
+        '\u{0DD2}' | '\u{0DD4}' => Sign(false),
+        '\u{102D}' | '\u{102F}' => Sign(false),
+        '\u{0E31}' | '\u{0E34}' | '\u{0E38}' => Sign(false),
+        '\u{17B7}' | '\u{17BB}' => Sign(false),
+        '\u{093E}' | '\u{0940}' | '\u{0942}' | '\u{0947}' | '\u{0948}' | '\u{094B}'
+        | '\u{094C}' => Sign(true),
+        '\u{0DCF}' | '\u{0DD3}' | '\u{0DD6}' | '\u{0DD9}'..='\u{0DDE}' => Sign(true),
+        '\u{102B}' | '\u{102C}' | '\u{102E}' | '\u{1030}' | '\u{1031}' | '\u{1032}' => Sign(true),
+        '\u{0E32}' | '\u{0E33}' | '\u{0E35}'..='\u{0E37}' | '\u{0E39}' => Sign(true),
+        '\u{17B6}' | '\u{17B8}'..='\u{17BA}' | '\u{17BC}'..='\u{17C5}' => Sign(true),
+        '\u{0E40}'..='\u{0E44}' => Leading,
+        '\u{094D}' | '\u{0DCA}' | '\u{1039}' | '\u{103A}' | '\u{0E3A}' | '\u{17D2}' => Virama,
+        '\u{0901}' | '\u{0902}' | '\u{0D82}' | '\u{1036}' | '\u{0E4D}' | '\u{17C6}' => Anusvara,
+        '\u{093C}'
+        | '\u{1037}'
+        | '\u{1038}'
+        | '\u{0E47}'..='\u{0E4C}'
+        | '\u{17C7}'..='\u{17D1}' => Mark,
+        _ => return None,
+    })
+}
+
+fn units(text: &str) -> Vec<Unit> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let end_of = |i: usize| chars.get(i).map_or(text.len(), |&(b, _)| b);
+    let mut units: Vec<Unit> = Vec::new();
+    let mut push = |class, range: Range<usize>| units.push(Unit { class, range });
+
+    let mut leading = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        match indic(c) {
+            Some(Indic::Leading) => {
+                leading = Some(start);
+                i += 1;
+            }
+            Some(Indic::Consonant) => {
+                let prefixed = leading.take();
+                let start = prefixed.unwrap_or(start);
+                i += 1;
+                if chars
+                    .get(i)
+                    .is_some_and(|&(_, n)| indic(n) == Some(Indic::Virama))
+                {
+                    i += 1;
+                    push(Class::Consonant, start..end_of(i));
+                    continue;
+                }
+                push(Class::Consonant, start..end_of(i));
+                while let Some(&(b, _)) = chars
+                    .get(i)
+                    .filter(|&&(_, n)| indic(n) == Some(Indic::Medial))
+                {
+                    i += 1;
+                    push(Class::Consonant, b..end_of(i));
+                }
+                // Vowel signs, or the inherent a.
+                let vowel_start = end_of(i);
+                let mut long = prefixed.map(|_| true);
+                while let Some(&(_, n)) = chars.get(i) {
+                    match indic(n) {
+                        Some(Indic::Sign(l)) => long = Some(long.unwrap_or(false) || l),
+                        Some(Indic::Mark) => {}
+                        _ => break,
+                    }
+                    i += 1;
+                }
+                let class = if long == Some(true) {
+                    Class::LongVowel
+                } else {
+                    Class::ShortVowel
+                };
+                push(class, vowel_start..end_of(i));
+            }
+            Some(Indic::Medial) => {
+                i += 1;
+                push(Class::Consonant, start..end_of(i));
+            }
+            Some(Indic::Vowel(l) | Indic::Sign(l)) => {
+                i += 1;
+                push(
+                    if l {
+                        Class::LongVowel
+                    } else {
+                        Class::ShortVowel
+                    },
+                    start..end_of(i),
+                );
+            }
+            Some(Indic::Anusvara) => {
+                i += 1;
+                push(Class::Niggahita, start..end_of(i));
+            }
+            Some(Indic::Virama | Indic::Mark) => i += 1,
+            None if c.is_alphabetic() => {
+                // A Roman letter with any combining marks.
+                i += 1;
+                while chars
+                    .get(i)
+                    .is_some_and(|&(_, n)| matches!(n, '\u{0300}'..='\u{036F}'))
+                {
+                    i += 1;
+                }
+                let letter = normalize_pali(&text[start..end_of(i)])
+                    .chars()
+                    .next()
+                    .unwrap_or(c);
+                let class = match letter {
+                    'a' | 'i' | 'u' => Class::ShortVowel,
+                    'ā' | 'ī' | 'ū' | 'e' | 'o' => Class::LongVowel,
+                    'ṃ' => Class::Niggahita,
+                    _ => Class::Consonant,
+                };
+                if STOPS.contains(&letter)
+                    && chars
+                        .get(i)
+                        .is_some_and(|&(_, n)| n.eq_ignore_ascii_case(&'h'))
+                {
+                    i += 1;
+                }
+                push(class, start..end_of(i));
+            }
+            None => {
+                i += 1;
+                push(Class::Break, start..end_of(i));
+            }
+        }
+    }
+    units
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(text: &str) -> (Vec<&str>, String) {
+        let syllables = syllabify(text);
+        let parts = syllables.iter().map(|s| &text[s.range.clone()]).collect();
+        (parts, pattern(&syllables))
+    }
+
+    #[test]
+    fn test_roman_syllables() {
+        let (parts, weights) = split("Manopubbaṅgamā dhammā");
+        assert_eq!(
+            parts,
+            vec!["Ma", "no", "pub", "baṅ", "ga", "mā", "dham", "mā"]
+        );
+        assert_eq!(weights, "⏑–––⏑–––");
+
+        // A final short vowel is heavy before a cluster in the next word.
+        let (_, weights) = split("ca brāhmaṇo");
+        assert_eq!(weights, "––⏑–");
+        let (parts, _) = split("manoseṭṭhā");
+        assert_eq!(parts, vec!["ma", "no", "seṭ", "ṭhā"]);
+    }
+
+    #[test]
+    fn test_indic_syllables() {
+        // dhammā in Devanagari, Sinhala and Myanmar
+        for text in ["धम्मा", "ධම්මා", "ဓမ္မာ"] {
+            assert_eq!(split(text).1, "––", "{}", text);
+            assert_eq!(split(text).0.len(), 2, "{}", text);
+        }
+        assert_eq!(split("बुद्धो").1, "––");
+        assert_eq!(split("भगवा").1, "⏑⏑–");
+    }
+
+    #[test]
+    fn test_siloka() {
+        let padas = [
+            "Manopubbaṅgamā dhammā,",
+            "manoseṭṭhā manomayā;",
+            "Manasā ce paduṭṭhena,",
+            "bhāsati vā karoti vā;",
+            "Tato naṃ dukkhamanveti,",
+            "cakkaṃva vahato padaṃ.",
+        ];
+        assert_eq!(scan(&padas).first(), Some(&Metre::Siloka));
+        assert!(scan(&["Evaṃ me sutaṃ.", "Iti."]).is_empty());
+    }
+
+    #[test]
+    fn test_tutthubha_and_jagati() {
+        // Twelve syllables ending –⏑–⏑x, then eleven ending –⏑––
+        let jagati = [
+            "Kāmā hi citrā madhurā manoramā,",
+            "dumapphalānīva patanti māṇavā.",
+        ];
+        assert_eq!(scan(&jagati).first(), Some(&Metre::Jagati));
+        let tutthubha = [
+            "virūparūpena mathenti cittaṃ,",
+            "ādīnavaṃ kāmaguṇesu disvā.",
+        ];
+        assert_eq!(scan(&tutthubha).first(), Some(&Metre::Tutthubha));
+    }
+}
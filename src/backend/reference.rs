@@ -0,0 +1,322 @@
+use crate::backend::numeral::{self, NumericValue, dotted_number};
+use crate::backend::types::{Token, TokenKind};
+use std::ops::Range;
+use std::sync::LazyLock;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// A citation of a canonical text, e.g. "dī. ni. 1.157" or "M I 23".
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanonicalRef {
+    /// Collection code from data/sigla.tsv, e.g. "dn".
+    pub collection: String,
+    /// Volume or book, as written ("1", "I"); `None` for one-part locators.
+    pub book: Option<String>,
+    /// Paragraph, page or verse, e.g. "157" or "23.4".
+    pub locator: String,
+}
+
+impl CanonicalRef {
+    /// Short form such as "dn 1.157" or "mn I 23".
+    pub fn label(&self) -> String {
+        match &self.book {
+            Some(book) if is_roman(book) => {
+                format!("{} {} {}", self.collection, book, self.locator)
+            }
+            Some(book) => format!("{} {}.{}", self.collection, book, self.locator),
+            None => format!("{} {}", self.collection, self.locator),
+        }
+    }
+
+    /// Book and locator as one sortable number; Roman volumes count as
+    /// their value, so "M II 5" sorts after "M I 23".
+    pub fn value(&self) -> Option<NumericValue> {
+        let mut parts = match &self.book {
+            Some(book) if is_roman(book) => vec![numeral::roman_value(book)?],
+            Some(book) => numeral::parse(book)?.0,
+            None => Vec::new(),
+        };
+        parts.extend(numeral::parse(&self.locator)?.0);
+        Some(NumericValue(parts))
+    }
+}
+
+/// Canonical references in `text` with their byte ranges.
+pub fn find(text: &str) -> Vec<(Range<usize>, CanonicalRef)> {
+    let mut out = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut skip_to = 0;
+    for (i, c) in text.char_indices() {
+        let at_boundary = !prev.is_some_and(char::is_alphanumeric);
+        prev = Some(c);
+        if i < skip_to || !at_boundary {
+            continue;
+        }
+        let found = table().iter().find_map(|(siglum, collection)| {
+            let len = match_siglum(&text[i..], siglum)?;
+            let (end, book, locator) = locator(&text[i + len..])?;
+            // "A 5" is more likely a letter and a number than a citation.
+            if siglum.chars().count() == 1 && book.is_none() {
+                return None;
+            }
+            let reference = CanonicalRef {
+                collection: collection.clone(),
+                book,
+                locator,
+            };
+            Some((i..i + len + end, reference))
+        });
+        if let Some((range, reference)) = found {
+            skip_to = range.end;
+            out.push((range, reference));
+        }
+    }
+    out
+}
+
+/// True if `text` ends with a siglum, so a sentence break right after it
+/// falls inside a reference.
+pub fn ends_with_siglum(text: &str) -> bool {
+    let text = text.trim_end();
+    // Only the last few characters can hold a siglum.
+    let tail = text
+        .char_indices()
+        .rev()
+        .nth(MAX_SIGLUM_CHARS)
+        .map_or(0, |(i, _)| i);
+    let mut prev = text[..tail].chars().last();
+    text[tail..]
+        .char_indices()
+        .map(|(i, c)| (tail + i, c))
+        .any(|(i, c)| {
+            let at_boundary = !prev.is_some_and(char::is_alphanumeric);
+            prev = Some(c);
+            at_boundary
+                && table()
+                    .iter()
+                    .any(|(siglum, _)| match_siglum(&text[i..], siglum) == Some(text.len() - i))
+        })
+}
+
+/// True if `text` opens with a volume or number that can follow a siglum,
+/// such as "1.157" or "I 23".
+pub fn starts_with_locator(text: &str) -> bool {
+    locator(text).is_some()
+}
+
+/// Joins the tokens of each reference into one `TokenKind::Reference`
+/// token. References inside bracketed `Merged` tokens become sub-tokens.
+/// `text` is the sentence the tokens were cut from, starting at `base_offset`.
+pub fn join<'a>(tokens: Vec<Token<'a>>, text: &'a str, base_offset: usize) -> Vec<Token<'a>> {
+    let spans = find(text);
+    if spans.is_empty() {
+        return tokens;
+    }
+    let end_of = |t: &Token| t.offset + t.text.len() - base_offset;
+
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let start = tokens[i].offset - base_offset;
+        let whole = spans.iter().find_map(|(range, reference)| {
+            if range.start != start {
+                return None;
+            }
+            let last = (i..tokens.len()).find(|&j| end_of(&tokens[j]) >= range.end)?;
+            (end_of(&tokens[last]) == range.end).then_some((last, reference))
+        });
+        if let Some((last, reference)) = whole {
+            let mut token = Token::spanning(
+                &tokens[i],
+                &tokens[last],
+                text,
+                base_offset,
+                TokenKind::Reference,
+            );
+            token.reference = Some(reference.clone());
+            token.value = reference.value();
+            out.push(token);
+            i = last + 1;
+            continue;
+        }
+
+        let mut token = tokens[i].clone();
+        if token.kind == TokenKind::Merged {
+            let end = end_of(&token);
+            for (range, reference) in spans
+                .iter()
+                .filter(|(r, _)| r.start >= start && r.end <= end)
+            {
+                let local = range.start - start..range.end - start;
+                let mut sub = Token::new(
+                    token.id,
+                    base_offset + range.start,
+                    token.sub_text(local),
+                    TokenKind::Reference,
+                );
+                sub.reference = Some(reference.clone());
+                sub.value = reference.value();
+                token.sub_tokens.push(sub);
+            }
+        }
+        out.push(token);
+        i += 1;
+    }
+    out
+}
+
+// ---------------------------------------------------------
+// SIGLA
+// ---------------------------------------------------------
+
+const MAX_SIGLUM_CHARS: usize = 16;
+
+// Longest sigla first, so "dha. sa." wins over "dha. pa." prefixes and
+// "Dhp" over "D".
+static TABLE: LazyLock<Vec<(String, String)>> = LazyLock::new(|| {
+    let mut rows = parse_table(include_str!("../../data/sigla.tsv"));
+    rows.sort_by_key(|(siglum, _)| std::cmp::Reverse(siglum.chars().count()));
+    rows
+});
+
+fn table() -> &'static [(String, String)] {
+    &TABLE
+}
+
+// Tab-separated: siglum, collection.
+fn parse_table(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(siglum, collection)| (siglum.to_string(), collection.trim().to_string()))
+        .collect()
+}
+
+// Length of `siglum` at the start of `text`. A space in the siglum
+// matches any run of whitespace, including none.
+fn match_siglum(text: &str, siglum: &str) -> Option<usize> {
+    let exact = siglum.chars().any(char::is_uppercase);
+    let mut chars = text.char_indices().peekable();
+    for s in siglum.chars() {
+        if s == ' ' {
+            while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+            continue;
+        }
+        let (_, c) = chars.next()?;
+        let same = if exact {
+            c == s
+        } else {
+            c.to_lowercase().eq(s.to_lowercase())
+        };
+        if !same {
+            return None;
+        }
+    }
+    let len = chars.peek().map_or(text.len(), |&(i, _)| i);
+    // "Dhp" must not match the start of "Dhpa".
+    let next = text[len..].chars().next();
+    (siglum.ends_with('.') || next.is_some_and(char::is_whitespace)).then_some(len)
+}
+
+// ---------------------------------------------------------
+// LOCATORS
+// ---------------------------------------------------------
+
+fn is_roman(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| matches!(c, 'I' | 'V' | 'X'))
+}
+
+// Parses " I 23", " 1.157" or " 5" after a siglum into the end offset,
+// book and locator.
+fn locator(text: &str) -> Option<(usize, Option<String>, String)> {
+    let start = text.len() - text.trim_start().len();
+    let rest = &text[start..];
+
+    let volume_len = rest
+        .chars()
+        .take_while(|c| matches!(c, 'I' | 'V' | 'X'))
+        .count();
+    let (book, number_start) = if volume_len > 0 && rest[volume_len..].starts_with([' ', '.']) {
+        let after = &rest[volume_len + 1..];
+        let skip = after.len() - after.trim_start().len();
+        (
+            Some(rest[..volume_len].to_string()),
+            start + volume_len + 1 + skip,
+        )
+    } else {
+        (None, start)
+    };
+
+    let number_len = dotted_number(&text[number_start..])?;
+    let end = number_start + number_len;
+    if text[end..]
+        .chars()
+        .next()
+        .is_some_and(char::is_alphanumeric)
+    {
+        return None;
+    }
+    let number = &text[number_start..end];
+    Some(match (book, number.split_once('.')) {
+        (Some(book), _) => (end, Some(book), number.to_string()),
+        (None, Some((book, locator))) => (end, Some(book.to_string()), locator.to_string()),
+        (None, None) => (end, None, number.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    #[test]
+    fn test_find_references() {
+        let text = "dī. ni. 1.157; Ma. Ni. 2.10, (M I 23) and Dhp 5. Not: dī. ni. and Dhpa 5.";
+        let found: Vec<(&str, String)> = find(text)
+            .into_iter()
+            .map(|(r, reference)| (&text[r], reference.label()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("dī. ni. 1.157", "dn 1.157".to_string()),
+                ("Ma. Ni. 2.10", "mn 2.10".to_string()),
+                ("M I 23", "mn I 23".to_string()),
+                ("Dhp 5", "dhp 5".to_string()),
+            ]
+        );
+        let (_, dn) = &find("dī.ni. 1.157")[0];
+        assert_eq!(dn.book.as_deref(), Some("1"));
+        assert_eq!(dn.locator, "157");
+        let (_, mn) = &find("M IV 5")[0];
+        assert_eq!(mn.value(), Some(NumericValue(vec![4, 5])));
+        // A bare letter needs a volume to count as a siglum.
+        assert!(find("A 5 and S 12").is_empty());
+        assert_eq!(find("A 4.5")[0].1.label(), "an 4.5");
+    }
+
+    #[test]
+    fn test_reference_tokens() {
+        let result = process_text("Vuttañhetaṃ dī. ni. 1.157 ādīsu. Bhagavā [dī. ni. 2.3] āha.");
+        let tokens = &result.sentences[0].tokens;
+        assert_eq!(tokens[1].kind, TokenKind::Reference);
+        assert_eq!(tokens[1].text, "dī. ni. 1.157");
+        assert_eq!(tokens[2].text, "ādīsu");
+
+        let merged = &result.sentences[1].tokens[1];
+        assert_eq!(merged.kind, TokenKind::Merged);
+        assert_eq!(merged.sub_tokens[0].text, "dī. ni. 2.3");
+        assert_eq!(
+            merged.sub_tokens[0].reference.as_ref().unwrap().collection,
+            "dn"
+        );
+
+        // "kathā." is a siglum only before a locator.
+        let result = process_text("Niṭṭhitā kathā. So bhagavā viharati.");
+        assert_eq!(result.sentences.len(), 2);
+        let result = process_text("Vuttaṃ kathā. 1.5 ādīsu.");
+        assert_eq!(result.sentences.len(), 1);
+    }
+}
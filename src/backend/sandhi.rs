@@ -0,0 +1,395 @@
+use crate::backend::lexicon::Lexicon;
+use crate::backend::normalize::normalize_pali;
+use crate::backend::particles;
+use crate::backend::types::{Token, TokenKind};
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum SandhiRule {
+    /// Final vowel + iti, e.g. hotī’ti = hoti + iti.
+    Quotative,
+    /// Niggahīta assimilated before an enclitic, e.g. dhammañca = dhammaṃ + ca.
+    Niggahita,
+    /// Enclitic fused to a particle or after an apostrophe, e.g.
+    /// itipi = iti + pi, so’va = so + eva.
+    Enclitic,
+}
+
+/// One member of a split. `offset` and `len` are byte positions inside the
+/// analysed word; `base` is the restored, normalized form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SandhiPart {
+    pub offset: usize,
+    pub len: usize,
+    pub base: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SandhiSplit {
+    pub rule: SandhiRule,
+    pub parts: Vec<SandhiPart>,
+}
+
+/// Proposes splits for a word, most likely first. Empty if no junction
+/// rule applies. Words listed in `lexicon` are not split as junctions.
+pub fn analyze(word: &str, lexicon: &Lexicon) -> Vec<SandhiSplit> {
+    let mut splits = Vec::new();
+    quotative(word, lexicon, &mut splits);
+    niggahita(word, lexicon, &mut splits);
+    enclitic(word, &mut splits);
+    splits
+}
+
+/// Sub-tokens for the best split of `token`, with restored base forms as
+/// their normalized text.
+pub fn split_token<'a>(token: &Token<'a>, lexicon: &Lexicon) -> Vec<Token<'a>> {
+    let Some(best) = analyze(&token.text, lexicon).into_iter().next() else {
+        return Vec::new();
+    };
+    best.parts
+        .into_iter()
+        .map(|part| {
+            let text = token.sub_text(part.offset..part.offset + part.len);
+            let mut sub = Token::new(token.id, token.offset + part.offset, text, TokenKind::Word);
+            sub.normalized = Cow::Owned(part.base);
+            sub
+        })
+        .collect()
+}
+
+/// Joins `hotī` `’` `’` `’` `ti` back into the single fused word the
+/// quotative forms; the word tokenizer splits on repeated apostrophes.
+/// `text` is the sentence the tokens were cut from, starting at `base_offset`.
+pub fn join_quotatives<'a>(
+    tokens: Vec<Token<'a>>,
+    text: &'a str,
+    base_offset: usize,
+) -> Vec<Token<'a>> {
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let Some(end) = quotative_run(&tokens, i) else {
+            out.push(tokens[i].clone());
+            i += 1;
+            continue;
+        };
+        out.push(Token::spanning(
+            &tokens[i],
+            &tokens[end],
+            text,
+            base_offset,
+            TokenKind::Word,
+        ));
+        i = end + 1;
+    }
+    out
+}
+
+// ---------------------------------------------------------
+// RULES
+// ---------------------------------------------------------
+
+const APOSTROPHES: [char; 3] = ['’', '\'', '‘'];
+const MIN_STEM_CHARS: usize = 2;
+
+// (surface ending, nasal kept on the first member, enclitic)
+const JUNCTIONS: [(&str, &str, &str); 4] = [
+    ("ñca", "ñ", "ca"),
+    ("mpi", "m", "pi"),
+    ("ñeva", "ñ", "eva"),
+    ("meva", "m", "eva"),
+];
+
+// (surface, base) of enclitics that fuse without a nasal junction.
+const ENCLITICS: [(&str, &str); 5] = [
+    ("eva", "eva"),
+    ("pi", "pi"),
+    ("ca", "ca"),
+    ("vā", "vā"),
+    ("va", "eva"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Exception {
+    // Word ending never split before iti.
+    Ending,
+    // Whole word in -ti that is not a quotative.
+    NotQuotative,
+    // Whole word that merely ends like a junction.
+    NotJunction,
+}
+
+static EXCEPTIONS: LazyLock<Vec<(String, Exception)>> =
+    LazyLock::new(|| parse_table(include_str!("../../data/sandhi.tsv")));
+
+fn is_exception(word: &str, kind: Exception) -> bool {
+    EXCEPTIONS.iter().any(|(form, k)| {
+        *k == kind
+            && match kind {
+                Exception::Ending => word.ends_with(form.as_str()),
+                _ => word == form,
+            }
+    })
+}
+
+// A headword, or the imperative of a listed verb (muñca from muñcati).
+fn is_lexicon_word(word: &str, lexicon: &Lexicon) -> bool {
+    lexicon.contains(word) || lexicon.contains(&format!("{}ti", word))
+}
+
+fn quotative(word: &str, lexicon: &Lexicon, out: &mut Vec<SandhiSplit>) {
+    let Some(before_ti) = word.strip_suffix("ti") else {
+        return;
+    };
+    let stem = before_ti.trim_end_matches(APOSTROPHES);
+    let elided = stem.len() < before_ti.len();
+    let Some(last) = stem.chars().last() else {
+        return;
+    };
+    if stem.chars().count() < MIN_STEM_CHARS {
+        return;
+    }
+
+    let head = &stem[..stem.len() - last.len_utf8()];
+    let bases = match last {
+        'ā' | 'ī' | 'ū' => {
+            if !elided {
+                let normalized = normalize_pali(word);
+                if is_exception(&normalized, Exception::Ending)
+                    || is_exception(&normalized, Exception::NotQuotative)
+                    || lexicon.contains(&normalized)
+                {
+                    return;
+                }
+            }
+            let short = format!("{}{}", head, shorten(last));
+            // ā is more often an original long vowel (bhagavā); ī and ū
+            // are usually lengthened short ones (hoti, sādhu).
+            if last == 'ā' {
+                vec![stem.to_string(), short]
+            } else {
+                vec![short, stem.to_string()]
+            }
+        }
+        // kusalan’ti = kusalaṃ + iti
+        'n' if elided => vec![format!("{}ṃ", head)],
+        _ if elided => vec![stem.to_string()],
+        _ => return,
+    };
+
+    let iti = SandhiPart {
+        offset: before_ti.len(),
+        len: "ti".len(),
+        base: "iti".to_string(),
+    };
+    for base in bases {
+        out.push(SandhiSplit {
+            rule: SandhiRule::Quotative,
+            parts: vec![
+                SandhiPart {
+                    offset: 0,
+                    len: stem.len(),
+                    base: normalize_pali(&base).into_owned(),
+                },
+                iti.clone(),
+            ],
+        });
+    }
+}
+
+fn niggahita(word: &str, lexicon: &Lexicon, out: &mut Vec<SandhiSplit>) {
+    let normalized = normalize_pali(word);
+    if is_exception(&normalized, Exception::NotJunction) || is_lexicon_word(&normalized, lexicon) {
+        return;
+    }
+    for (ending, nasal, enclitic) in JUNCTIONS {
+        let Some(head) = word.strip_suffix(ending) else {
+            continue;
+        };
+        if head.chars().count() < MIN_STEM_CHARS {
+            continue;
+        }
+        let first_len = head.len() + nasal.len();
+        // taññeva = taṃ + eva: the nasal is doubled before a vowel.
+        let stem = head.strip_suffix(nasal).unwrap_or(head);
+        out.push(SandhiSplit {
+            rule: SandhiRule::Niggahita,
+            parts: vec![
+                SandhiPart {
+                    offset: 0,
+                    len: first_len,
+                    base: normalize_pali(&format!("{}ṃ", stem)).into_owned(),
+                },
+                SandhiPart {
+                    offset: first_len,
+                    len: word.len() - first_len,
+                    base: enclitic.to_string(),
+                },
+            ],
+        });
+    }
+}
+
+fn enclitic(word: &str, out: &mut Vec<SandhiSplit>) {
+    for (surface, base) in ENCLITICS {
+        let Some(head) = word.strip_suffix(surface) else {
+            continue;
+        };
+        let host = head.trim_end_matches(APOSTROPHES);
+        let elided = host.len() < head.len();
+        let host_base = normalize_pali(host);
+        if host.is_empty() || !(elided || particles::is_particle(&host_base)) {
+            continue;
+        }
+        // ’pi after an elided vowel stands for api.
+        let base = if elided && base == "pi" { "api" } else { base };
+        out.push(SandhiSplit {
+            rule: SandhiRule::Enclitic,
+            parts: vec![
+                SandhiPart {
+                    offset: 0,
+                    len: host.len(),
+                    base: host_base.into_owned(),
+                },
+                SandhiPart {
+                    offset: host.len(),
+                    len: word.len() - host.len(),
+                    base: base.to_string(),
+                },
+            ],
+        });
+        return;
+    }
+}
+
+fn shorten(vowel: char) -> char {
+    match vowel {
+        'ā' => 'a',
+        'ī' => 'i',
+        'ū' => 'u',
+        v => v,
+    }
+}
+
+// Tab-separated: form, rule.
+fn parse_table(text: &str) -> Vec<(String, Exception)> {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('\t'))
+        .filter_map(|(form, rule)| {
+            let rule = match rule.trim() {
+                "ending" => Exception::Ending,
+                "quotative" => Exception::NotQuotative,
+                "junction" => Exception::NotJunction,
+                _ => return None,
+            };
+            Some((normalize_pali(form.trim()).into_owned(), rule))
+        })
+        .collect()
+}
+
+// Word, one or more apostrophe tokens, then "ti", with no gaps between.
+fn quotative_run(tokens: &[Token], start: usize) -> Option<usize> {
+    let adjacent = |a: &Token, b: &Token| a.offset + a.text.len() == b.offset;
+    if tokens[start].kind != TokenKind::Word {
+        return None;
+    }
+    let mut end = start + 1;
+    while let Some(t) = tokens.get(end) {
+        let is_apostrophe =
+            t.kind == TokenKind::Punctuation && t.text.chars().all(|c| APOSTROPHES.contains(&c));
+        if !is_apostrophe || !adjacent(&tokens[end - 1], t) {
+            break;
+        }
+        end += 1;
+    }
+    let ti = tokens.get(end)?;
+    (end > start + 1
+        && ti.kind == TokenKind::Word
+        && ti.normalized == "ti"
+        && adjacent(&tokens[end - 1], ti))
+    .then_some(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::{SegOptions, process_text_with};
+
+    fn bases(split: &SandhiSplit) -> Vec<&str> {
+        split.parts.iter().map(|p| p.base.as_str()).collect()
+    }
+
+    #[test]
+    fn test_quotative_iti() {
+        let lexicon = Lexicon::bundled();
+        let splits = analyze("bhagavāti", lexicon);
+        assert_eq!(bases(&splits[0]), vec!["bhagavā", "iti"]);
+        assert_eq!(bases(&splits[1]), vec!["bhagava", "iti"]);
+        assert_eq!(splits[0].parts[1].offset, "bhagavā".len());
+
+        let splits = analyze("sādhū’ti", lexicon);
+        assert_eq!(bases(&splits[0]), vec!["sādhu", "iti"]);
+        assert_eq!(splits[0].parts[0].len, "sādhū".len());
+
+        // Verbs from the sample text are not junctions.
+        for verb in ["viharati", "pavedeti", "deseti", "pakāseti", "jānāti"] {
+            assert!(analyze(verb, lexicon).is_empty(), "{}", verb);
+        }
+        // Nouns in -āti, -īti, -ūti without an apostrophe stay whole.
+        for noun in ["jāti", "pīti", "yāti", "bhūti", "Jāti"] {
+            assert!(analyze(noun, lexicon).is_empty(), "{}", noun);
+        }
+    }
+
+    #[test]
+    fn test_niggahita_junctions() {
+        let lexicon = Lexicon::bundled();
+        assert_eq!(
+            bases(&analyze("dhammañca", lexicon)[0]),
+            vec!["dhammaṃ", "ca"]
+        );
+        assert_eq!(bases(&analyze("evameva", lexicon)[0]), vec!["evaṃ", "eva"]);
+        assert!(analyze("pañca", lexicon).is_empty());
+
+        let splits = analyze("taññeva", lexicon);
+        assert_eq!(bases(&splits[0]), vec!["taṃ", "eva"]);
+        assert_eq!(splits[0].parts[0].len, "taññ".len());
+        // Imperative of muñcati
+        assert!(analyze("muñca", lexicon).is_empty());
+        // The active lexicon decides, not only the bundled one.
+        assert!(!analyze("muñca", &Lexicon::default()).is_empty());
+        let loaded = Lexicon::from_text("vañcati");
+        assert!(analyze("vañca", &loaded).is_empty());
+    }
+
+    #[test]
+    fn test_sample_text_splits() {
+        // Last clause of the sample sutta in app.rs
+        let text = "sādhu kho pana tathārūpānaṃ arahataṃ dassanaṃ hotī’’’ti.";
+        let options = SegOptions {
+            sandhi: true,
+            ..Default::default()
+        };
+        let result = process_text_with(text, &options);
+        let tokens = &result.sentences[0].tokens;
+
+        let fused = tokens.iter().find(|t| t.text == "hotī’’’ti").unwrap();
+        let parts: Vec<(&str, &str)> = fused
+            .sub_tokens
+            .iter()
+            .map(|t| (t.text.as_ref(), t.normalized.as_ref()))
+            .collect();
+        assert_eq!(parts, vec![("hotī", "hoti"), ("ti", "iti")]);
+        assert_eq!(fused.sub_tokens[1].offset, text.find("ti.").unwrap());
+
+        // Ids stay consecutive after joining.
+        let ids: Vec<usize> = tokens.iter().map(|t| t.id).collect();
+        assert_eq!(ids, (1..=tokens.len()).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::backend::marker::LocatorKind;
+use crate::backend::outline;
+use crate::backend::types::{SegResult, Sentence};
+use std::collections::HashMap;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Sets `segment_id` on every sentence and pāda to a SuttaCentral-style
+/// "uid:paragraph.sentence", e.g. "mn1:1.2". Paragraphs follow the VRI
+/// paragraph markers when the text has any, numbering text before the
+/// first one 0 as SuttaCentral does for titles; otherwise each line break
+/// opens the next paragraph. A number before a heading ("1. Verañjakaṇḍaṃ")
+/// is not a paragraph marker. A paragraph number seen again opens a new
+/// paragraph with its occurrence appended, "1_2", so ids stay unique and
+/// in document order.
+pub fn assign(result: &mut SegResult, uid: &str) {
+    let numbered = result
+        .segments()
+        .iter()
+        .any(|s| paragraph_marker(s).is_some());
+    let mut segments: Vec<&mut Sentence> = result.segments_mut().collect();
+    segments.sort_by_key(|s| s.tokens.first().map_or(0, |t| t.offset));
+
+    let mut paragraph = if numbered { "0" } else { "1" }.to_string();
+    let mut next_paragraph = 1;
+    let mut count = 0;
+    // Times each paragraph number has occurred so far.
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for sentence in segments {
+        if let Some(value) = paragraph_marker(sentence) {
+            let occurrence = seen.entry(value.clone()).or_default();
+            *occurrence += 1;
+            paragraph = match *occurrence {
+                1 => value,
+                n => format!("{}_{}", value, n),
+            };
+            count = 0;
+        } else if !numbered && sentence.paragraph_start && count > 0 {
+            next_paragraph += 1;
+            paragraph = next_paragraph.to_string();
+            count = 0;
+        }
+        count += 1;
+        sentence.segment_id = Some(format!("{}:{}.{}", uid, paragraph, count));
+    }
+}
+
+// Number of a VRI paragraph marker in the sentence, without leading zeros.
+fn paragraph_marker(sentence: &Sentence) -> Option<String> {
+    if outline::is_heading(sentence) {
+        return None;
+    }
+    let locator = sentence
+        .locators
+        .iter()
+        .find(|l| l.kind == LocatorKind::Para)?;
+    let value = locator.value.trim_start_matches('0');
+    Some(if value.is_empty() { "0" } else { value }.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::pli_segmenter::process_text;
+
+    fn ids(text: &str, uid: &str) -> Vec<String> {
+        let mut result = process_text(text);
+        assign(&mut result, uid);
+        result
+            .segments()
+            .iter()
+            .map(|s| s.segment_id.clone().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_ids_from_markers() {
+        let text = "Mūlapariyāyasutta\n\n1. Evaṃ me sutaṃ. Ekaṃ samayaṃ bhagavā viharati.\nTatra kho bhagavā āmantesi.\n\n2. Bhikkhavo ti.";
+        assert_eq!(
+            ids(text, "mn1"),
+            vec!["mn1:0.1", "mn1:1.1", "mn1:1.2", "mn1:1.3", "mn1:2.1"]
+        );
+    }
+
+    #[test]
+    fn test_ids_from_lines() {
+        let text = "Evaṃ me sutaṃ. Ekaṃ samayaṃ bhagavā viharati.\n\nTatra kho bhagavā āmantesi.";
+        assert_eq!(ids(text, "dn2"), vec!["dn2:1.1", "dn2:1.2", "dn2:2.1"]);
+    }
+
+    #[test]
+    fn test_ids_are_unique() {
+        let text = "Pārājikapāḷi\n\n1. Verañjakaṇḍaṃ\n\n1. Tena samayena buddho bhagavā verañjāyaṃ viharati.\n\n2. Assosi kho verañjo brāhmaṇo.\n\n1. Atha kho verañjo brāhmaṇo.";
+        let ids = ids(text, "pj1");
+        assert_eq!(
+            ids,
+            ["pj1:0.1", "pj1:0.2", "pj1:1.1", "pj1:2.1", "pj1:1_2.1"].map(String::from)
+        );
+        let mut unique = ids.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), ids.len(), "{:?}", ids);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::backend::normalize::normalize_pali;
+use std::borrow::Cow;
+use std::ops::Range;
+use unicode_normalization::UnicodeNormalization;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// Strips Latin diacritics: ā→a, ṃ→m, ñ→n, ṭ→t, … Other scripts are left
+/// untouched, so Myanmar or Thai vowel signs survive.
+pub fn ascii_fold(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.nfd()
+            .filter(|c| !matches!(c, '\u{0300}'..='\u{036F}'))
+            .collect(),
+    )
+}
+
+/// Plain-keyboard schemes for typing Roman Pali.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputScheme {
+    Velthuis,
+    HarvardKyoto,
+}
+
+impl InputScheme {
+    pub fn label(self) -> &'static str {
+        match self {
+            InputScheme::Velthuis => "Velthuis",
+            InputScheme::HarvardKyoto => "Harvard-Kyoto",
+        }
+    }
+
+    /// Letter/input pairs, usable as a cheat sheet.
+    pub fn table(self) -> &'static [(char, &'static str)] {
+        match self {
+            InputScheme::Velthuis => VELTHUIS,
+            InputScheme::HarvardKyoto => HARVARD_KYOTO,
+        }
+    }
+}
+
+/// Velthuis encoding (aa, .m, "n, ~n, .t, …) of Roman Pali.
+pub fn to_velthuis(text: &str) -> Cow<'_, str> {
+    encode(text, VELTHUIS)
+}
+
+/// Harvard-Kyoto encoding (A, M, G, J, T, …) of Roman Pali.
+pub fn to_harvard_kyoto(text: &str) -> Cow<'_, str> {
+    encode(text, HARVARD_KYOTO)
+}
+
+/// Converts typed input ("aa", ".m", "~n" or "A", "M", "J") to Roman Pali.
+/// Already converted letters pass through.
+pub fn convert_input(text: &str, scheme: InputScheme) -> Cow<'_, str> {
+    decode(text, scheme.table())
+}
+
+/// Byte range of the text inserted by an edit from `before` to `after`.
+pub fn inserted(before: &str, after: &str) -> Range<usize> {
+    let prefix: usize = before
+        .chars()
+        .zip(after.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    let suffix: usize = before[prefix..]
+        .chars()
+        .rev()
+        .zip(after[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    prefix..after.len() - suffix
+}
+
+/// Converts a single typed character in `text`, together with the letters
+/// before it that it may complete as a code ("a" then "a" is ā). Returns
+/// the new text and the byte offset after the conversion, or `None` if
+/// nothing changes. Other text, such as capitals in pasted English, is
+/// left as it is.
+pub fn convert_typed(
+    text: &str,
+    typed: Range<usize>,
+    scheme: InputScheme,
+) -> Option<(String, usize)> {
+    if text[typed.clone()].chars().count() != 1 {
+        return None;
+    }
+    let lookback = scheme
+        .table()
+        .iter()
+        .map(|(_, code)| code.chars().count())
+        .max()
+        .unwrap_or(1)
+        - 1;
+    let start = text[..typed.start]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| !c.is_whitespace())
+        .take(lookback)
+        .last()
+        .map_or(typed.start, |(i, _)| i);
+    let Cow::Owned(converted) = convert_input(&text[start..typed.end], scheme) else {
+        return None;
+    };
+    let end = start + converted.len();
+    Some((
+        format!("{}{}{}", &text[..start], converted, &text[typed.end..]),
+        end,
+    ))
+}
+
+/// Search key for plain-keyboard queries: normalized, then ASCII-folded.
+pub fn search_key(text: &str) -> String {
+    ascii_fold(&normalize_pali(text)).into_owned()
+}
+
+/// True if `query` occurs in `key` once both are reduced to search keys.
+/// `key` is expected to be a search key already.
+pub fn loose_match(key: &str, query: &str) -> bool {
+    let query = search_key(query.trim());
+    !query.is_empty() && key.contains(query.as_str())
+}
+
+// ---------------------------------------------------------
+// ENCODING TABLES
+// ---------------------------------------------------------
+
+// First entry per letter is the one used for encoding.
+const VELTHUIS: &[(char, &str)] = &[
+    ('ā', "aa"),
+    ('ī', "ii"),
+    ('ū', "uu"),
+    ('ṃ', ".m"),
+    ('ṅ', "\"n"),
+    ('ñ', "~n"),
+    ('ṭ', ".t"),
+    ('ḍ', ".d"),
+    ('ṇ', ".n"),
+    ('ḷ', ".l"),
+    ('ṛ', ".r"),
+    ('ś', "\"s"),
+    ('ṣ', ".s"),
+    ('ḥ', ".h"),
+    ('Ā', "AA"),
+    ('Ā', "Aa"),
+    ('Ī', "II"),
+    ('Ī', "Ii"),
+    ('Ū', "UU"),
+    ('Ū', "Uu"),
+];
+
+const HARVARD_KYOTO: &[(char, &str)] = &[
+    ('ā', "A"),
+    ('ī', "I"),
+    ('ū', "U"),
+    ('ṃ', "M"),
+    ('ṅ', "G"),
+    ('ñ', "J"),
+    ('ṭ', "T"),
+    ('ḍ', "D"),
+    ('ṇ', "N"),
+    ('ḷ', "L"),
+    ('ṛ', "R"),
+    ('ś', "z"),
+    ('ṣ', "S"),
+    ('ḥ', "H"),
+];
+
+fn encode<'a>(text: &'a str, table: &[(char, &str)]) -> Cow<'a, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match table.iter().find(|(letter, _)| *letter == c) {
+            Some((_, code)) => out.push_str(code),
+            None => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn decode<'a>(text: &'a str, table: &[(char, &str)]) -> Cow<'a, str> {
+    if !table.iter().any(|(_, code)| text.contains(code)) {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match table.iter().find(|(_, code)| rest.starts_with(code)) {
+            Some((letter, code)) => {
+                out.push(*letter);
+                rest = &rest[code.len()..];
+            }
+            None => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_fold() {
+        assert_eq!(ascii_fold("bhikkhusaṅghena"), "bhikkhusanghena");
+        assert_eq!(ascii_fold("naḷerupucimandamūle"), "nalerupucimandamule");
+        assert_eq!(ascii_fold("ဘဂဝါ"), "ဘဂဝါ");
+    }
+
+    #[test]
+    fn test_encodings() {
+        assert_eq!(to_velthuis("verañjāyaṃ"), "vera~njaaya.m");
+        assert_eq!(to_harvard_kyoto("bhikkhusaṅghena"), "bhikkhusaGghena");
+        assert_eq!(to_harvard_kyoto("brāhmaṇo"), "brAhmaNo");
+    }
+
+    #[test]
+    fn test_convert_input() {
+        let velthuis = "bhikkhusa\"nghena saddhi.m pa~ncamattehi, Aananda";
+        assert_eq!(
+            convert_input(velthuis, InputScheme::Velthuis),
+            "bhikkhusaṅghena saddhiṃ pañcamattehi, Ānanda"
+        );
+        assert_eq!(
+            convert_input("verajAyaM naLerupucimandamUle", InputScheme::HarvardKyoto),
+            "verajāyaṃ naḷerupucimandamūle"
+        );
+        // Round trip, and converted text is stable.
+        let pali = "brāhmaṇo kittisaddo abbhuggato";
+        let typed = to_velthuis(pali);
+        assert_eq!(convert_input(&typed, InputScheme::Velthuis), pali);
+        assert!(matches!(
+            convert_input(pali, InputScheme::Velthuis),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_convert_typed() {
+        let hk = InputScheme::HarvardKyoto;
+        // Typing "M" after "Tena ta" converts only the new letter.
+        let text = "Tena taM";
+        assert_eq!(inserted("Tena ta", text), 7..8);
+        assert_eq!(
+            convert_typed(text, 7..8, hk),
+            Some(("Tena taṃ".to_string(), "Tena taṃ".len()))
+        );
+        assert_eq!(convert_typed("Tena ta", 6..7, hk), None);
+        // A second "a" completes the Velthuis code for ā.
+        let text = "Aanandaa bhik";
+        let typed = inserted("Aananda bhik", text);
+        assert_eq!(
+            convert_typed(text, typed, InputScheme::Velthuis),
+            Some(("Aanandā bhik".to_string(), "Aanandā".len()))
+        );
+        // Pasted text is left alone.
+        assert_eq!(convert_typed("Tena", 0..4, hk), None);
+    }
+
+    #[test]
+    fn test_loose_match() {
+        let key = search_key("Bhikkhusaṅghena");
+        assert!(loose_match(&key, "sangh"));
+        assert!(loose_match(&key, "SAṄGHENA"));
+        assert!(!loose_match(&key, "  "));
+    }
+}
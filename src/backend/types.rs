@@ -1,29 +1,175 @@
+use crate::backend::compound::CompoundAnalysis;
+use crate::backend::conjugation::VerbAnalysis;
+use crate::backend::elision::Expansion;
+use crate::backend::formula::RepeatedPassage;
+use crate::backend::list_marker::ListMarker;
+use crate::backend::marker::Locator;
+use crate::backend::morphology::MorphAnalysis;
+use crate::backend::normalize::normalize_pali;
+use crate::backend::numeral::NumericValue;
+use crate::backend::orthography::Orthography;
+use crate::backend::outline::Heading;
+use crate::backend::prosody::Metre;
+use crate::backend::reference::CanonicalRef;
+use crate::backend::translit::{ascii_fold, loose_match, to_harvard_kyoto, to_velthuis};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct SegResult<'a> {
     pub sentences: Vec<Sentence<'a>>,
+    /// Notes produced while preparing or analysing the text.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Maps offsets in the segmented text back to the text as it was given.
+    pub offset_map: OffsetMap,
+    /// Passages filling peyyāla markers, when expansion is enabled.
+    pub expansions: Vec<Expansion>,
+    /// Passages repeated within this text. Filled by `formula::annotate`.
+    pub formulas: Vec<RepeatedPassage>,
+    /// Verse blocks, segmented into pādas instead of sentences.
+    pub stanzas: Vec<Stanza<'a>>,
+    /// Headings and numbered paragraphs. Filled by `outline::annotate`.
+    pub outline: Vec<Heading>,
 }
 
 impl<'a> SegResult<'a> {
     pub fn to_owned_data(&self) -> SegResult<'static> {
         SegResult {
             sentences: self.sentences.iter().map(|s| s.to_owned_data()).collect(),
+            diagnostics: self.diagnostics.clone(),
+            offset_map: self.offset_map.clone(),
+            expansions: self.expansions.clone(),
+            formulas: self.formulas.clone(),
+            stanzas: self.stanzas.iter().map(|s| s.to_owned_data()).collect(),
+            outline: self.outline.clone(),
         }
     }
+
+    /// Segments with a number of value `n`, in digits or words.
+    pub fn mentioning(&self, n: u64) -> Vec<&Sentence<'a>> {
+        self.segments()
+            .into_iter()
+            .filter(|s| {
+                s.tokens
+                    .iter()
+                    .any(|t| t.value.as_ref().and_then(NumericValue::as_integer) == Some(n))
+            })
+            .collect()
+    }
+
+    /// Prose sentences and verse pādas, in document order.
+    pub fn segments(&self) -> Vec<&Sentence<'a>> {
+        let mut all: Vec<&Sentence<'a>> = self
+            .sentences
+            .iter()
+            .chain(self.stanzas.iter().flat_map(|s| &s.padas))
+            .collect();
+        all.sort_by_key(|s| s.tokens.first().map_or(0, |t| t.offset));
+        all
+    }
+
+    /// Prose sentences and verse pādas, for annotation passes.
+    pub fn segments_mut(&mut self) -> impl Iterator<Item = &mut Sentence<'a>> {
+        self.sentences
+            .iter_mut()
+            .chain(self.stanzas.iter_mut().flat_map(|s| s.padas.iter_mut()))
+    }
+
+    /// Word counts with spelling variants counted together, listed under
+    /// the first normalized spelling met, most frequent first.
+    pub fn word_frequencies(&self) -> Vec<(&str, usize)> {
+        let mut counts: HashMap<&str, (&str, usize)> = HashMap::new();
+        for token in self.segments().into_iter().flat_map(|s| &s.tokens) {
+            if token.kind == TokenKind::Word {
+                counts
+                    .entry(token.key.as_ref())
+                    .or_insert((token.normalized.as_ref(), 0))
+                    .1 += 1;
+            }
+        }
+        let mut counts: Vec<_> = counts.into_values().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts
+    }
+
+    /// Word counts keyed by lemma, falling back to the normalized form
+    /// for words without one.
+    pub fn lemma_frequencies(&self) -> Vec<(&str, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for token in self.segments().into_iter().flat_map(|s| &s.tokens) {
+            if token.kind == TokenKind::Word {
+                let key = token.lemma.as_deref().unwrap_or(&token.normalized);
+                *counts.entry(key).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Sentence<'a> {
     pub text: Cow<'a, str>,
     pub tokens: Vec<Token<'a>>,
+    /// Page and paragraph markers inside the sentence.
+    pub locators: Vec<Locator>,
+    /// List marker opening the sentence, e.g. "(ka)".
+    pub list_marker: Option<ListMarker>,
+    /// True if only blank space precedes the sentence on its line.
+    pub paragraph_start: bool,
+    /// Hierarchical id such as "mn1:1.2". Filled by `segment_id::assign`.
+    pub segment_id: Option<String>,
 }
 
 impl<'a> Sentence<'a> {
+    /// The last word with a finite verbal reading and no attested nominal
+    /// one, counting sandhi parts (hotī’ti). Pali clauses are mostly
+    /// verb-final.
+    pub fn main_verb(&self) -> Option<&Token<'a>> {
+        let is_finite = |t: &Token| {
+            t.verbs.iter().any(|v| v.form.is_finite()) && !t.morphology.iter().any(|m| m.attested)
+        };
+        self.tokens
+            .iter()
+            .rev()
+            .filter(|t| t.kind == TokenKind::Word)
+            .find(|t| is_finite(t) || t.sub_tokens.iter().any(is_finite))
+    }
+
     pub fn to_owned_data(&self) -> Sentence<'static> {
         Sentence {
             text: Cow::Owned(self.text.clone().into_owned()),
             tokens: self.tokens.iter().map(|t| t.to_owned_data()).collect(),
+            locators: self.locators.clone(),
+            list_marker: self.list_marker.clone(),
+            paragraph_start: self.paragraph_start,
+            segment_id: self.segment_id.clone(),
+        }
+    }
+}
+
+/// A gāthā. Each pāda is segmented like a sentence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stanza<'a> {
+    pub offset: usize,
+    pub text: Cow<'a, str>,
+    /// Verse number as written, e.g. "12".
+    pub number: Option<String>,
+    pub padas: Vec<Sentence<'a>>,
+    /// Metres the pādas scan as, best first.
+    pub metres: Vec<Metre>,
+}
+
+impl<'a> Stanza<'a> {
+    pub fn to_owned_data(&self) -> Stanza<'static> {
+        Stanza {
+            offset: self.offset,
+            text: Cow::Owned(self.text.clone().into_owned()),
+            number: self.number.clone(),
+            padas: self.padas.iter().map(|p| p.to_owned_data()).collect(),
+            metres: self.metres.clone(),
         }
     }
 }
@@ -34,15 +180,126 @@ pub struct Token<'a> {
     pub offset: usize,
     pub text: Cow<'a, str>,
     pub kind: TokenKind,
+    /// Edition-neutral form used for comparison, search and counting.
+    pub normalized: Cow<'a, str>,
+    /// `normalized` with edition spelling differences levelled (saṅgha =
+    /// saṃgha). Set by `Orthography::apply`.
+    pub key: Cow<'a, str>,
+    /// Finer units inside this token (e.g. Myanmar syllables). Sub-tokens
+    /// share the id of their parent and carry absolute offsets.
+    pub sub_tokens: Vec<Token<'a>>,
+    /// Ranked compound decompositions, best first. Filled by
+    /// `compound::annotate`; empty for simple words.
+    pub compounds: Vec<CompoundAnalysis>,
+    /// Nominal stem + case readings. Filled by `morphology::annotate`.
+    pub morphology: Vec<MorphAnalysis>,
+    /// Verbal readings. Filled by `conjugation::annotate`.
+    pub verbs: Vec<VerbAnalysis>,
+    /// Dictionary headword. Filled by `lemma::annotate`.
+    pub lemma: Option<String>,
+    /// Parsed citation of a `TokenKind::Reference` token.
+    pub reference: Option<CanonicalRef>,
+    /// Sortable value of numbers, references and page or paragraph
+    /// markers.
+    pub value: Option<NumericValue>,
 }
 
 impl<'a> Token<'a> {
+    pub fn new(id: usize, offset: usize, text: Cow<'a, str>, kind: TokenKind) -> Self {
+        Self {
+            id,
+            offset,
+            normalized: text.clone(),
+            key: text.clone(),
+            text,
+            kind,
+            sub_tokens: Vec::new(),
+            compounds: Vec::new(),
+            morphology: Vec::new(),
+            verbs: Vec::new(),
+            lemma: None,
+            reference: None,
+            value: None,
+        }
+    }
+
+    /// One token covering `first` through `last`, cut from `text`, which
+    /// starts at `base_offset`. Takes the id of `first`.
+    pub fn spanning(
+        first: &Token<'a>,
+        last: &Token<'a>,
+        text: &'a str,
+        base_offset: usize,
+        kind: TokenKind,
+    ) -> Token<'a> {
+        let joined = &text[first.offset - base_offset..last.offset + last.text.len() - base_offset];
+        let mut token = Token::new(first.id, first.offset, Cow::Borrowed(joined), kind);
+        token.normalized = normalize_pali(joined);
+        token
+    }
+
+    /// Part of this token's text, borrowed when the text is borrowed.
+    /// `range` is relative to the start of the token.
+    pub fn sub_text(&self, range: Range<usize>) -> Cow<'a, str> {
+        match &self.text {
+            Cow::Borrowed(t) => Cow::Borrowed(&t[range]),
+            Cow::Owned(t) => Cow::Owned(t[range].to_string()),
+        }
+    }
+
+    /// True if both tokens are spelling variants of the same word.
+    pub fn same_word(&self, other: &Token) -> bool {
+        self.key == other.key
+    }
+
+    /// Diacritic-free form of `key` for plain-keyboard search.
+    pub fn ascii_key(&self) -> Cow<'_, str> {
+        ascii_fold(&self.key)
+    }
+
+    pub fn velthuis(&self) -> Cow<'_, str> {
+        to_velthuis(&self.normalized)
+    }
+
+    pub fn harvard_kyoto(&self) -> Cow<'_, str> {
+        to_harvard_kyoto(&self.normalized)
+    }
+
+    /// Loose match against a query typed with or without diacritics,
+    /// keyed with the default orthography.
+    pub fn matches_query(&self, query: &str) -> bool {
+        self.matches_keys(&Orthography::default().query_keys(query))
+    }
+
+    /// Folded forms a search query is compared with: the spelling key and
+    /// the normalized text.
+    pub fn search_forms(&self) -> [Cow<'_, str>; 2] {
+        [self.ascii_key(), ascii_fold(&self.normalized)]
+    }
+
+    /// True if any of `keys`, from `Orthography::query_keys`, occurs in
+    /// one of the token's search forms.
+    pub fn matches_keys(&self, keys: &[String]) -> bool {
+        let forms = self.search_forms();
+        keys.iter()
+            .any(|k| forms.iter().any(|form| loose_match(form, k)))
+    }
+
     pub fn to_owned_data(&self) -> Token<'static> {
         Token {
             id: self.id,
             offset: self.offset,
             text: Cow::Owned(self.text.clone().into_owned()),
             kind: self.kind,
+            normalized: Cow::Owned(self.normalized.clone().into_owned()),
+            key: Cow::Owned(self.key.clone().into_owned()),
+            sub_tokens: self.sub_tokens.iter().map(|t| t.to_owned_data()).collect(),
+            compounds: self.compounds.clone(),
+            morphology: self.morphology.clone(),
+            verbs: self.verbs.clone(),
+            lemma: self.lemma.clone(),
+            reference: self.reference.clone(),
+            value: self.value.clone(),
         }
     }
 }
@@ -50,8 +307,78 @@ impl<'a> Token<'a> {
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub enum TokenKind {
     Word,
+    /// Indeclinable particle or enclitic (kho, pana, ca, iti, …).
+    Particle,
+    /// Peyyāla marker standing for a repeated passage (…pe…, ฯลฯ).
+    Elision,
+    /// Citation of a canonical text (dī. ni. 1.157, M I 23).
+    Reference,
+    /// Edition page marker ([PTS 1.2], M.1.5).
+    PageMarker,
+    /// Paragraph number (VRI "1." at paragraph start).
+    ParaMarker,
     Number,
     Punctuation,
     Merged,
+    Syllable,
     Other,
 }
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A message about a span of the source text. `offset` and `len` are byte
+/// positions in the text as it was given by the user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub offset: usize,
+    pub len: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// A span that was rewritten by a pre-processing step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OffsetSpan {
+    pub original: Range<usize>,
+    pub rewritten: Range<usize>,
+}
+
+/// Records which spans were rewritten so offsets can be mapped back.
+/// An empty map is the identity.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct OffsetMap {
+    spans: Vec<OffsetSpan>,
+}
+
+impl OffsetMap {
+    /// Spans must be pushed in increasing order.
+    pub fn push(&mut self, original: Range<usize>, rewritten: Range<usize>) {
+        self.spans.push(OffsetSpan {
+            original,
+            rewritten,
+        });
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn spans(&self) -> &[OffsetSpan] {
+        &self.spans
+    }
+
+    /// Offsets inside a rewritten span map to the start of its original span.
+    pub fn to_original(&self, offset: usize) -> usize {
+        let idx = self.spans.partition_point(|s| s.rewritten.start <= offset);
+        match idx.checked_sub(1).map(|i| &self.spans[i]) {
+            None => offset,
+            Some(span) if offset < span.rewritten.end => span.original.start,
+            Some(span) => span.original.end + (offset - span.rewritten.end),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::backend::prosody;
+use std::ops::Range;
+
+// ---------------------------------------------------------
+// PUBLIC API
+// ---------------------------------------------------------
+
+/// A verse block found in the text, with byte ranges of its pādas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StanzaSpan {
+    pub range: Range<usize>,
+    pub padas: Vec<Range<usize>>,
+    /// Verse number as written, e.g. "12" from "(12)" or "॥ १२ ॥".
+    pub number: Option<String>,
+}
+
+/// Finds gāthās: blocks of short lines of even length, or blocks divided
+/// by dandas. Blocks are separated by blank lines.
+pub fn find_stanzas(text: &str) -> Vec<StanzaSpan> {
+    blocks(text)
+        .into_iter()
+        .filter_map(|block| stanza(text, block))
+        .collect()
+}
+
+/// Syllable count used to compare line lengths.
+pub fn syllable_count(text: &str) -> usize {
+    prosody::syllabify(text).len()
+}
+
+// ---------------------------------------------------------
+// DETECTION
+// ---------------------------------------------------------
+
+const DANDAS: [char; 3] = ['।', '॥', '|'];
+const LINE_END_PUNCTUATION: [char; 6] = [',', ';', '.', '?', '!', ':'];
+
+const MIN_PADA_SYLLABLES: usize = 5;
+const MAX_PADA_SYLLABLES: usize = 13;
+const MAX_LENGTH_SPREAD: usize = 4;
+const MAX_LINES: usize = 8;
+
+// Runs of non-blank lines, each as trimmed line ranges.
+fn blocks(text: &str) -> Vec<Vec<Range<usize>>> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+        } else {
+            let start = offset + (line.len() - line.trim_start().len());
+            current.push(start..start + trimmed.len());
+        }
+        offset += line.len();
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+fn stanza(text: &str, mut lines: Vec<Range<usize>>) -> Option<StanzaSpan> {
+    let range = lines.first()?.start..lines.last()?.end;
+
+    // Trailing verse number, possibly on its own line.
+    let last = lines.pop()?;
+    let (body_end, number) = split_number(&text[last.clone()]);
+    let number = number.map(str::to_string);
+    if body_end > 0 {
+        lines.push(last.start..last.start + body_end);
+    }
+    if lines.is_empty() {
+        return None;
+    }
+
+    let block = lines[0].start..lines.last()?.end;
+    let padas = if text[block.clone()].contains(DANDAS) {
+        // A single danda is also the prose full stop.
+        if number.is_none() && !closes_verse(&text[block.clone()]) {
+            return None;
+        }
+        danda_padas(text, block)?
+    } else {
+        line_padas(text, &lines)?
+    };
+    (padas.len() >= 2).then_some(StanzaSpan {
+        range,
+        padas,
+        number,
+    })
+}
+
+// Splits "… (12)", "… ‖ 12 ‖", "… ॥ १२ ॥" into body length and number.
+fn split_number(line: &str) -> (usize, Option<&str>) {
+    let trimmed = line.trim_end();
+    let (open, close) = match trimmed.chars().last() {
+        Some(')') => ("(", ")"),
+        Some('॥') => ("॥", "॥"),
+        Some('‖') => ("‖", "‖"),
+        Some('|') if trimmed.ends_with("||") => ("||", "||"),
+        _ => return (line.len(), None),
+    };
+    let inner = &trimmed[..trimmed.len() - close.len()];
+    let Some(start) = inner.rfind(open) else {
+        return (line.len(), None);
+    };
+    let number = inner[start + open.len()..].trim();
+    if number.is_empty() || !number.chars().all(char::is_numeric) {
+        return (line.len(), None);
+    }
+    (inner[..start].trim_end().len(), Some(number))
+}
+
+fn closes_verse(block: &str) -> bool {
+    block.contains(['॥', '‖']) || block.contains("||")
+}
+
+// Pādas or half-verses between dandas, of similar length as for lines.
+fn danda_padas(text: &str, block: Range<usize>) -> Option<Vec<Range<usize>>> {
+    let mut padas = Vec::new();
+    let mut start = block.start;
+    for (i, c) in text[block.clone()].char_indices() {
+        if DANDAS.contains(&c) || c == '\n' {
+            push_trimmed(text, start..block.start + i, &mut padas);
+            start = block.start + i + c.len_utf8();
+        }
+    }
+    push_trimmed(text, start..block.end, &mut padas);
+
+    let counts: Vec<usize> = padas
+        .iter()
+        .map(|p| syllable_count(&text[p.clone()]))
+        .collect();
+    let (min, max) = (*counts.iter().min()?, *counts.iter().max()?);
+    (max - min <= MAX_LENGTH_SPREAD && min >= MIN_PADA_SYLLABLES && max <= 2 * MAX_PADA_SYLLABLES)
+        .then_some(padas)
+}
+
+// One pāda per line, or two when every line is a half-verse.
+fn line_padas(text: &str, lines: &[Range<usize>]) -> Option<Vec<Range<usize>>> {
+    if lines.len() < 2 || lines.len() > MAX_LINES {
+        return None;
+    }
+    // Headings and numbered paragraphs: "Verañjakaṇḍaṃ", "1. Verañja…"
+    let not_pada = |l: &Range<usize>| {
+        let line = &text[l.clone()];
+        line.split_whitespace().count() < 2 || line.starts_with(char::is_numeric)
+    };
+    if lines.iter().any(not_pada) {
+        return None;
+    }
+    let counts: Vec<usize> = lines
+        .iter()
+        .map(|l| syllable_count(&text[l.clone()]))
+        .collect();
+    let (min, max) = (*counts.iter().min()?, *counts.iter().max()?);
+    if max - min > MAX_LENGTH_SPREAD {
+        return None;
+    }
+
+    if min >= MIN_PADA_SYLLABLES && max <= MAX_PADA_SYLLABLES {
+        return Some(lines.to_vec());
+    }
+    let half_verses = min >= 2 * MIN_PADA_SYLLABLES
+        && max <= 2 * MAX_PADA_SYLLABLES
+        && lines[..lines.len() - 1]
+            .iter()
+            .all(|l| text[l.clone()].ends_with(LINE_END_PUNCTUATION));
+    if !half_verses {
+        return None;
+    }
+    let mut padas = Vec::new();
+    for (line, count) in lines.iter().zip(counts) {
+        let split = caesura(&text[line.clone()], count)?;
+        push_trimmed(text, line.start..line.start + split, &mut padas);
+        push_trimmed(text, line.start + split..line.end, &mut padas);
+    }
+    Some(padas)
+}
+
+// The space closest to the middle of the line by syllables.
+fn caesura(line: &str, count: usize) -> Option<usize> {
+    line.char_indices()
+        .filter(|&(_, c)| c.is_whitespace())
+        .min_by_key(|&(i, _)| syllable_count(&line[..i]).abs_diff(count / 2))
+        .map(|(i, _)| i)
+}
+
+fn push_trimmed(text: &str, range: Range<usize>, out: &mut Vec<Range<usize>>) {
+    let piece = &text[range.clone()];
+    let start = range.start + (piece.len() - piece.trim_start().len());
+    let end = start + piece.trim().len();
+    if start < end {
+        out.push(start..end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_verse() {
+        let text = "Evaṃ me sutaṃ.\n\n\
+                    Manopubbaṅgamā dhammā,\n\
+                    manoseṭṭhā manomayā;\n\
+                    Manasā ce paduṭṭhena,\n\
+                    bhāsati vā karoti vā;\n\
+                    Tato naṃ dukkhamanveti,\n\
+                    cakkaṃva vahato padaṃ. (1)\n";
+        let stanzas = find_stanzas(text);
+        assert_eq!(stanzas.len(), 1);
+        assert_eq!(stanzas[0].padas.len(), 6);
+        assert_eq!(stanzas[0].number.as_deref(), Some("1"));
+        assert_eq!(&text[stanzas[0].padas[5].clone()], "cakkaṃva vahato padaṃ.");
+        assert!(text[stanzas[0].range.clone()].ends_with("(1)"));
+    }
+
+    #[test]
+    fn test_half_verse_lines_and_dandas() {
+        let text = "Manopubbaṅgamā dhammā, manoseṭṭhā manomayā;\n\
+                    Manasā ce paduṭṭhena, bhāsati vā karoti vā.";
+        let padas: Vec<&str> = find_stanzas(text)[0]
+            .padas
+            .iter()
+            .map(|p| &text[p.clone()])
+            .collect();
+        assert_eq!(padas[0], "Manopubbaṅgamā dhammā,");
+        assert_eq!(padas[3], "bhāsati vā karoti vā.");
+
+        let devanagari = "मनोपुब्बङ्गमा धम्मा मनोसेट्ठा मनोमया ।\nमनसा चे पदुट्ठेन भासति वा करोति वा ॥ १ ॥";
+        let stanza = &find_stanzas(devanagari)[0];
+        assert_eq!(stanza.padas.len(), 2);
+        assert_eq!(stanza.number.as_deref(), Some("१"));
+    }
+
+    #[test]
+    fn test_prose_is_not_verse() {
+        let text = "Tena samayena buddho bhagavā verañjāyaṃ viharati naḷerupucimandamūle\n\
+                    mahatā bhikkhusaṅghena saddhiṃ pañcamattehi bhikkhusatehi. Assosi kho\n\
+                    verañjo brāhmaṇo.";
+        assert!(find_stanzas(text).is_empty());
+        assert_eq!(syllable_count("bhikkhusaṅghena"), 5);
+    }
+
+    #[test]
+    fn test_danda_prose_and_headings() {
+        let text = "तेन समयेन बुद्धो भगवा वेरञ्जायं विहरति नळेरुपुचिमन्दमूले महता भिक्खुसङ्घेन सद्धिं पञ्चमत्तेहि भिक्खुसतेहि । अस्सोसि खो वेरञ्जो ब्राह्मणो ।";
+        assert!(find_stanzas(text).is_empty());
+        let result = crate::backend::pli_segmenter::process_text(text);
+        assert_eq!(result.sentences.len(), 2);
+
+        assert!(find_stanzas("Verañjakaṇḍaṃ\n1. Verañjabrāhmaṇavatthu").is_empty());
+    }
+}
@@ -1,15 +1,89 @@
+use crate::backend::lexicon::Lexicon;
+use crate::backend::translit::{InputScheme, convert_typed, inserted};
+use leptos::html::Textarea;
 use leptos::prelude::*; // <--- IMPORT THIS
+use std::sync::Arc;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
 
 #[component]
-pub fn InputUI(set_text: WriteSignal<String>) -> impl IntoView {
+pub fn InputUI(
+    set_text: WriteSignal<String>,
+    set_lexicon: WriteSignal<Arc<Lexicon>>,
+    uid: ReadSignal<String>,
+    set_uid: WriteSignal<String>,
+) -> impl IntoView {
+    // Optional live conversion of plain-keyboard input
+    let (scheme, set_scheme) = signal(None::<InputScheme>);
+    let textarea_ref = NodeRef::<Textarea>::new();
+
+    // Text as last seen, to find what each keystroke inserted
+    let last_value = StoredValue::new(String::new());
+
+    let on_input = move |ev| {
+        let value = event_target_value(&ev);
+        let typed = inserted(&last_value.get_value(), &value);
+        // Convert only the letter just typed, so existing text such as
+        // "Tena" keeps its capitals; without the textarea, change nothing.
+        let converted = scheme.get_untracked().and_then(|scheme| {
+            let el = textarea_ref.get()?;
+            let (converted, caret) = convert_typed(&value, typed, scheme)?;
+            // Selection offsets are UTF-16
+            let caret = converted[..caret].encode_utf16().count() as u32;
+            el.set_value(&converted);
+            let _ = el.set_selection_range(caret, caret);
+            Some(converted)
+        });
+        let value = converted.unwrap_or(value);
+        last_value.set_value(value.clone());
+        set_text.set(value);
+    };
+
+    let on_scheme_change = move |ev| {
+        let scheme = match event_target_value(&ev).as_str() {
+            "velthuis" => Some(InputScheme::Velthuis),
+            "hk" => Some(InputScheme::HarvardKyoto),
+            _ => None,
+        };
+        set_scheme.set(scheme);
+    };
+
+    // Replace the bundled headword list with one read from a local file
+    let (lexicon_size, set_lexicon_size) = signal(Lexicon::bundled().len());
+    let on_lexicon_file = move |ev| {
+        let input: HtmlInputElement = event_target(&ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        leptos::task::spawn_local(async move {
+            if let Ok(text) = JsFuture::from(file.text()).await
+                && let Some(text) = text.as_string()
+            {
+                let lexicon = Lexicon::from_text(&text);
+                set_lexicon_size.set(lexicon.len());
+                set_lexicon.set(Arc::new(lexicon));
+            }
+        });
+    };
+
     view! {
         <div class="input-container" style="margin-bottom: 2rem; background: #fff; padding: 1.5rem; border-radius: 12px; box-shadow: 0 4px 6px rgba(0,0,0,0.05); border: 1px solid #f0f0f0;">
-            <label
-                for="text-input"
-                style="display: block; margin-bottom: 0.8rem; font-weight: 600; color: #333; font-size: 1.1em;"
-            >
-                "Enter Text to Analyze"
-            </label>
+            <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: 0.8rem;">
+                <label
+                    for="text-input"
+                    style="font-weight: 600; color: #333; font-size: 1.1em;"
+                >
+                    "Enter Text to Analyze"
+                </label>
+                <label style="font-size: 0.85em; color: #555;">
+                    "Input method: "
+                    <select on:change=on_scheme_change style="font-size: 1em;">
+                        <option value="off">"Off"</option>
+                        <option value="velthuis">"Velthuis"</option>
+                        <option value="hk">"Harvard-Kyoto"</option>
+                    </select>
+                </label>
+            </div>
             <style>
                 "
                 #text-input:focus {
@@ -22,10 +96,42 @@ pub fn InputUI(set_text: WriteSignal<String>) -> impl IntoView {
             <textarea
                 id="text-input"
                 rows=6
-                on:input=move |ev| set_text.set(event_target_value(&ev))
+                node_ref=textarea_ref
+                on:input=on_input
                 placeholder="Type or paste text here (English, Pali, etc.)..."
                 style="width: 100%; box-sizing: border-box; padding: 1rem; border-radius: 8px; border: 1px solid #e0e0e0; font-family: 'Segoe UI', sans-serif; font-size: 1rem; line-height: 1.5; resize: vertical; transition: border-color 0.2s, box-shadow 0.2s;"
             />
+
+            // Cheat sheet for the selected input method
+            {move || scheme.get().map(|scheme| view! {
+                <details style="margin-top: 0.6rem; font-size: 0.85em; color: #555;">
+                    <summary style="cursor: pointer;">{format!("{} cheat sheet", scheme.label())}</summary>
+                    <div style="display: flex; flex-wrap: wrap; gap: 6px 14px; margin-top: 0.5rem;">
+                        {scheme.table().iter().map(|(letter, code)| view! {
+                            <span>
+                                <code style="background: #f5f5f5; padding: 1px 4px; border-radius: 4px;">{*code}</code>
+                                {format!(" → {}", letter)}
+                            </span>
+                        }).collect_view()}
+                    </div>
+                </details>
+            })}
+
+            <label style="display: block; margin-top: 0.6rem; font-size: 0.85em; color: #555;">
+                "Lexicon: "
+                <input type="file" accept=".txt,.tsv,text/plain" on:change=on_lexicon_file />
+                {move || format!(" {} headwords", lexicon_size.get())}
+            </label>
+            <label style="display: block; margin-top: 0.6rem; font-size: 0.85em; color: #555;">
+                "Text uid: "
+                <input
+                    type="text"
+                    prop:value=uid
+                    on:input=move |ev| set_uid.set(event_target_value(&ev))
+                    placeholder="e.g. mn1"
+                    style="font-size: 1em; width: 12rem;"
+                />
+            </label>
         </div>
     }
 }
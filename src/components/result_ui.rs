@@ -1,82 +1,439 @@
-use crate::backend::types::{SegResult, TokenKind};
+use crate::backend::citation::resolve;
+use crate::backend::export::{to_data_uri, to_tsv};
+use crate::backend::orthography::Orthography;
+use crate::backend::outline::Level;
+use crate::backend::prosody::{pattern, syllabify};
+use crate::backend::translit::loose_match;
+use crate::backend::types::{Diagnostic, SegResult, Sentence, Severity, Stanza, Token, TokenKind};
 use leptos::prelude::*; // Updated import
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+// Predefined palette of background colors (pastel/vibrant)
+const COLORS: [&str; 8] = [
+    "#ffd90079", // LightGold
+    "#ffb6c17a", // LightPink
+    "#87cefaa9", // LightSkyBlue
+    "#90ee908b", // LightGreen
+    "#f4c6f4cb", // LightPlum
+    "#ffb99da2", // LightSalmon
+    "#20b2ab68", // LightSeaGreen
+    "#f0e68c68", // LightKhaki
+];
+
+/// Prose sentence or verse stanza, listed in document order.
+#[derive(Clone)]
+enum Block {
+    Prose(Sentence<'static>),
+    Verse(Stanza<'static>),
+}
+
+impl Block {
+    fn offset(&self) -> usize {
+        match self {
+            Block::Prose(s) => s.tokens.first().map_or(0, |t| t.offset),
+            Block::Verse(s) => s.offset,
+        }
+    }
+
+    // Element id the outline links to.
+    fn anchor(&self) -> String {
+        format!("seg-{}", self.offset())
+    }
+
+    fn tokens(&self) -> Vec<&Token<'static>> {
+        match self {
+            Block::Prose(s) => s.tokens.iter().collect(),
+            Block::Verse(s) => s.padas.iter().flat_map(|p| &p.tokens).collect(),
+        }
+    }
+
+    // Rows are reused while the key holds, so it covers everything a row
+    // shows: segment ids change with the uid, and lemmas, warnings or
+    // formulas with the lexicon or input scheme, while the text stays.
+    fn key(&self, data: &SegResult<'static>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        match self {
+            Block::Prose(s) => format!("{:?}", s).hash(&mut hasher),
+            Block::Verse(s) => format!("verse:{:?}", s).hash(&mut hasher),
+        }
+        let tokens = self.tokens();
+        let ids: Vec<usize> = tokens.iter().map(|t| t.id).collect();
+        let span = tokens.first().zip(tokens.last()).map(|(first, last)| {
+            data.offset_map.to_original(first.offset)
+                ..data.offset_map.to_original(last.offset + last.text.len())
+        });
+        for diagnostic in &data.diagnostics {
+            if span
+                .as_ref()
+                .is_some_and(|s| s.contains(&diagnostic.offset))
+            {
+                format!("{:?}", diagnostic).hash(&mut hasher);
+            }
+        }
+        for expansion in data.expansions.iter().filter(|e| ids.contains(&e.token_id)) {
+            format!("{:?}", expansion).hash(&mut hasher);
+        }
+        for formula in &data.formulas {
+            if formula
+                .occurrences
+                .iter()
+                .any(|o| ids.iter().any(|id| o.tokens.contains(id)))
+            {
+                format!("{:?}", formula).hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+// Blocks in document order with their row keys.
+fn blocks(data: &SegResult<'static>) -> Vec<(u64, Block)> {
+    let mut blocks: Vec<Block> = data
+        .sentences
+        .iter()
+        .cloned()
+        .map(Block::Prose)
+        .chain(data.stanzas.iter().cloned().map(Block::Verse))
+        .collect();
+    blocks.sort_by_key(Block::offset);
+    blocks
+        .into_iter()
+        .map(|block| (block.key(data), block))
+        .collect()
+}
 
 #[component]
 pub fn ResultUI(
     /// The processed data passed down from the parent
     data: Signal<SegResult<'static>>,
+    /// Spelling rules the token keys were built with
+    orthography: Orthography,
 ) -> impl IntoView {
-    // Predefined palette of background colors (pastel/vibrant)
-    let colors = [
-        "#ffd90079", // LightGold
-        "#ffb6c17a", // LightPink
-        "#87cefaa9", // LightSkyBlue
-        "#90ee908b", // LightGreen
-        "#f4c6f4cb", // LightPlum
-        "#ffb99da2", // LightSalmon
-        "#20b2ab68", // LightSeaGreen
-        "#f0e68c68", // LightKhaki
-    ];
+    // Search query, matched without diacritics
+    let (query, set_query) = signal(String::new());
+    let query_keys = Memo::new(move |_| query.with(|q| orthography.query_keys(q)));
+    // Show peyyāla markers filled from earlier formulas
+    let (expand, set_expand) = signal(false);
+    // Fold repeats of a formula after its first occurrence
+    let (collapse, set_collapse) = signal(false);
+    let export_href = move || to_data_uri("text/tab-separated-values", &to_tsv(&data.get()));
 
     view! {
         <div class="results-container" style="background-color: #ffffff; padding: 2rem; border-radius: 12px; box-shadow: 0 4px 6px rgba(0,0,0,0.05); border: 1px solid #f0f0f0;">
 
+            // Pre-processing and analysis notes
+            <Show when=move || !data.get().diagnostics.is_empty()>
+                <details style="margin-bottom: 1.5rem; font-size: 0.85em; color: #555;">
+                    <summary style="cursor: pointer; font-weight: 600;">
+                        {move || format!("Notes ({})", data.get().diagnostics.len())}
+                    </summary>
+                    <ul style="margin: 0.5rem 0 0; padding-left: 1.2rem;">
+                        {move || data.get().diagnostics.into_iter().map(|diag| {
+                            let color = match diag.severity {
+                                Severity::Info => "#6c757d",
+                                Severity::Warning => "#b58105",
+                                Severity::Error => "#c0392b",
+                            };
+                            view! {
+                                <li style=format!("color: {};", color)>
+                                    {format!("[{}..{}] {}", diag.offset, diag.offset + diag.len, diag.message)}
+                                </li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                </details>
+            </Show>
+
+            <div style="display: flex; gap: 1.5rem; align-items: flex-start;">
+            // Outline: headings and numbered paragraphs, linked to their cards
+            <Show when=move || !data.get().outline.is_empty()>
+                <nav style="position: sticky; top: 1rem; flex: 0 0 180px; max-height: 80vh; overflow-y: auto; font-size: 0.8em; border-right: 1px solid #f0f0f0; padding-right: 0.8rem;">
+                    <div style="color: #888; text-transform: uppercase; letter-spacing: 0.05em; font-weight: 600; margin-bottom: 0.6rem;">"Outline"</div>
+                    {move || data.get().outline.into_iter().map(|heading| {
+                        let weight = if heading.level == Level::Paragraph { "normal" } else { "600" };
+                        view! {
+                            <a
+                                href=format!("#seg-{}", heading.offset)
+                                title=format!("{}{}", heading.level.label(), heading.segment_id.map(|id| format!(" · {}", id)).unwrap_or_default())
+                                style=format!("display: block; padding: 2px 0 2px {}rem; color: #555; text-decoration: none; font-weight: {};", heading.level.depth() as f32 * 0.8, weight)
+                            >
+                                {heading.label()}
+                            </a>
+                        }
+                    }).collect_view()}
+                </nav>
+            </Show>
+
             // List of Segments
-            <div>
-                <span style="color: #888; font-size: 0.85em; text-transform: uppercase; letter-spacing: 0.05em; font-weight: 600; display: block; margin-bottom: 1rem;">"Analysis Result"</span>
+            <div style="flex: 1; min-width: 0;">
+                <div style="display: flex; align-items: center; gap: 1rem; margin-bottom: 1rem;">
+                    <span style="color: #888; font-size: 0.85em; text-transform: uppercase; letter-spacing: 0.05em; font-weight: 600;">"Analysis Result"</span>
+                    <input
+                        type="search"
+                        placeholder="Search (e.g. sangha, 500)"
+                        on:input=move |ev| set_query.set(event_target_value(&ev))
+                        style="flex: 1; padding: 0.4rem 0.6rem; border-radius: 6px; border: 1px solid #e0e0e0; font-size: 0.9em;"
+                    />
+                    <Show when=move || !data.get().expansions.is_empty()>
+                        <label style="font-size: 0.85em; color: #555; white-space: nowrap;">
+                            <input
+                                type="checkbox"
+                                on:change=move |ev| set_expand.set(event_target_checked(&ev))
+                            />
+                            " Expand pe"
+                        </label>
+                    </Show>
+                    <Show when=move || !data.get().formulas.is_empty()>
+                        <label style="font-size: 0.85em; color: #555; white-space: nowrap;">
+                            <input
+                                type="checkbox"
+                                on:change=move |ev| set_collapse.set(event_target_checked(&ev))
+                            />
+                            " Collapse formulas"
+                        </label>
+                    </Show>
+                    <a
+                        href=export_href
+                        download="tokens.tsv"
+                        style="font-size: 0.85em; color: #1e88e5; text-decoration: none;"
+                    >
+                        "Export TSV"
+                    </a>
+                </div>
                 <ul style="list-style-type: none; padding: 0; margin: 0;">
                     <For
-                        each=move || data.get().sentences
-                        key=|sent| sent.text.clone()
-                        children=move |sentence| {
-                            view! {
-                                <li style="margin-bottom: 1.5rem; padding: 1.5rem; border-radius: 10px; background-color: #fcfcfc; border: 1px solid #f0f0f0;">
+                        each=move || blocks(&data.get())
+                        key=|(key, _)| *key
+                        children=move |(_, block)| { let anchor = block.anchor(); match block {
+                            Block::Prose(sentence) => view! {
+                                <li id=anchor style="margin-bottom: 1.5rem; padding: 1.5rem; border-radius: 10px; background-color: #fcfcfc; border: 1px solid #f0f0f0;">
+                                    {sentence.segment_id.clone().map(|id| view! {
+                                        <div style="float: right; color: #aaa; font-size: 0.75em; font-family: monospace;">{id}</div>
+                                    })}
+                                    {sentence.list_marker.as_ref().map(|m| view! {
+                                        <div style="color: #888; font-size: 0.75em; font-weight: 600; margin-bottom: 0.4rem;" title=format!("{:?} list, {:?}", m.series, m.enclosure)>
+                                            {match m.number {
+                                                Some(n) => format!("List item {} ({})", n, m.label),
+                                                None => format!("List item ({})", m.label),
+                                            }}
+                                        </div>
+                                    })}
                                     <div style="display: flex; flex-wrap: wrap; gap: 8px; line-height: 1.6;">
-                                        {sentence.tokens.into_iter().enumerate().map(|(i, token)| {
-                                            let base_style = "padding: 4px 8px; border-radius: 6px; font-size: 0.95em; transition: transform 0.1s;";
-
-                                            let (specific_style, kind_label) = match token.kind {
-                                                TokenKind::Word => {
-                                                    // Use index to cycle through colors
-                                                    let color = colors[i % colors.len()];
-                                                    (format!("background-color: {}; color: #333; font-weight: 500;", color), "Word")
-                                                },
-                                                TokenKind::Number => (
-                                                    "background-color: #e9ecef; color: #495057; font-weight: bold; border: 1px solid #ced4da;".to_string(),
-                                                    "Number"
-                                                ),
-                                                TokenKind::Punctuation => (
-                                                    "background-color: #f0f0f0ff; color: #333; border-radius: 100%; padding: 4px 8px; font-weight: bold;".to_string(),
-                                                    "Punctuation"
-                                                ),
-                                                TokenKind::Merged => (
-                                                    "background-color: #fff3cd; color: #856404; border: 1px solid #ffeeba;".to_string(),
-                                                    "Merged Segment"
-                                                ),
-                                                TokenKind::Other => (
-                                                    "color: #6c757d;".to_string(),
-                                                    "Other"
-                                                ),
-                                            };
-
-                                            let tooltip = format!("ID: {}, Offset: {}, Kind: {:?}", token.id, token.offset, kind_label);
-
-                                            view! {
-                                                <span
-                                                    style=format!("{} {}", base_style, specific_style)
-                                                    title=tooltip
-                                                >
-                                                    {token.text}
-                                                </span>
-                                            }
-                                        }).collect_view()}
+                                        {token_chips(sentence, data, query, query_keys, expand, collapse)}
                                     </div>
                                 </li>
-                            }
-                        }
+                            }.into_any(),
+                            // Verse: one line per pāda, even pādas indented
+                            Block::Verse(stanza) => view! {
+                                <li id=anchor style="margin-bottom: 1.5rem; padding: 1.5rem; border-radius: 10px; background-color: #fbf8ff; border: 1px solid #f0f0f0; border-left: 4px solid #b39ddb;">
+                                    <div style="color: #7e57c2; font-size: 0.8em; font-weight: 600; margin-bottom: 0.6rem;">
+                                        {match &stanza.number {
+                                            Some(n) => format!("Gāthā {}", n),
+                                            None => "Gāthā".to_string(),
+                                        }}
+                                        {stanza.metres.first().map(|m| format!(" · {}", m.label()))}
+                                    </div>
+                                    {stanza.padas.into_iter().enumerate().map(|(p, pada)| {
+                                        let indent = if p % 2 == 1 { "2rem" } else { "0" };
+                                        let scansion = pattern(&syllabify(&pada.text));
+                                        let segment_id = pada.segment_id.clone();
+                                        view! {
+                                            <div style=format!("display: flex; flex-wrap: wrap; align-items: center; gap: 8px; line-height: 1.6; margin: 0 0 0.4rem {};", indent)>
+                                                {token_chips(pada, data, query, query_keys, expand, collapse)}
+                                                <span style="margin-left: auto; color: #b39ddb; font-size: 0.8em; letter-spacing: 0.1em;" title="Garu (–) and lahu (⏑)">
+                                                    {scansion}
+                                                </span>
+                                                <span style="color: #aaa; font-size: 0.75em; font-family: monospace;">
+                                                    {segment_id}
+                                                </span>
+                                            </div>
+                                        }
+                                    }).collect_view()}
+                                </li>
+                            }.into_any(),
+                        }}
                     />
                 </ul>
             </div>
+            </div>
         </div>
     }
 }
+
+/// One chip per token of a sentence or pāda.
+fn token_chips(
+    sentence: Sentence<'static>,
+    data: Signal<SegResult<'static>>,
+    query: ReadSignal<String>,
+    query_keys: Memo<Vec<String>>,
+    expand: ReadSignal<bool>,
+    collapse: ReadSignal<bool>,
+) -> impl IntoView {
+    let main_verb = sentence.main_verb().map(|t| t.id);
+    let locators = sentence.locators.clone();
+    // Read once per row; `Block::key` changes when these do.
+    let expansions = data.with_untracked(|d| d.expansions.clone());
+    let formulas = data.with_untracked(|d| d.formulas.clone());
+    let (diagnostics, offset_map) =
+        data.with_untracked(|d| (d.diagnostics.clone(), d.offset_map.clone()));
+    sentence.tokens.into_iter().enumerate().map(move |(i, token)| {
+        let base_style = "padding: 4px 8px; border-radius: 6px; font-size: 0.95em; transition: transform 0.1s;";
+
+        let (specific_style, kind_label) = match token.kind {
+            TokenKind::Word => {
+                // Use index to cycle through colors
+                let color = COLORS[i % COLORS.len()];
+                (format!("background-color: {}; color: #333; font-weight: 500;", color), "Word")
+            },
+            TokenKind::Particle => (
+                "color: #999; font-size: 0.85em; border: 1px solid #eee;".to_string(),
+                "Particle"
+            ),
+            TokenKind::Elision => (
+                "color: #6c757d; font-style: italic; border: 1px dashed #ced4da;".to_string(),
+                "Elision"
+            ),
+            TokenKind::Reference => (
+                "color: #1e88e5; border: 1px solid #bbdefb; text-decoration: underline dotted;".to_string(),
+                "Reference"
+            ),
+            TokenKind::PageMarker | TokenKind::ParaMarker => (
+                "color: #8d6e63; font-size: 0.75em; font-family: monospace; border: 1px solid #d7ccc8; align-self: center;".to_string(),
+                if token.kind == TokenKind::PageMarker { "Page Marker" } else { "Para Marker" }
+            ),
+            TokenKind::Number => (
+                "background-color: #e9ecef; color: #495057; font-weight: bold; border: 1px solid #ced4da;".to_string(),
+                "Number"
+            ),
+            TokenKind::Punctuation => (
+                "background-color: #f0f0f0ff; color: #333; border-radius: 100%; padding: 4px 8px; font-weight: bold;".to_string(),
+                "Punctuation"
+            ),
+            TokenKind::Merged => (
+                "background-color: #fff3cd; color: #856404; border: 1px solid #ffeeba;".to_string(),
+                "Merged Segment"
+            ),
+            TokenKind::Syllable => (
+                "background-color: #e8f4fd; color: #333; border: 1px dashed #87cefa;".to_string(),
+                "Syllable"
+            ),
+            TokenKind::Other => (
+                "color: #6c757d;".to_string(),
+                "Other"
+            ),
+        };
+
+        let mut tooltip = format!("ID: {}, Offset: {}, Kind: {:?}", token.id, token.offset, kind_label);
+        if token.normalized != token.text {
+            tooltip.push_str(&format!("\nNormalized: {}", token.normalized));
+        }
+        if token.key != token.normalized {
+            tooltip.push_str(&format!("\nSpelling key: {}", token.key));
+        }
+        // Warnings inside this token, e.g. impossible clusters
+        let (start, end) = (offset_map.to_original(token.offset), offset_map.to_original(token.offset + token.text.len()));
+        let warnings: Vec<&Diagnostic> = diagnostics
+            .iter()
+            .filter(|d| d.severity != Severity::Info && d.offset >= start && d.offset + d.len <= end)
+            .collect();
+        for warning in &warnings {
+            tooltip.push_str(&format!("\n⚠ {}", warning.message));
+        }
+        let squiggle = if warnings.is_empty() { "" } else { " text-decoration: underline wavy #c0392b;" };
+        let references: Vec<String> = token
+            .reference
+            .iter()
+            .chain(token.sub_tokens.iter().filter_map(|t| t.reference.as_ref()))
+            .map(|r| match resolve(r) {
+                Some(citation) => format!("{} → {}", r.label(), citation.label()),
+                None => r.label(),
+            })
+            .collect();
+        if !references.is_empty() {
+            tooltip.push_str(&format!("\nReference: {}", references.join("; ")));
+        }
+        if !token.sub_tokens.is_empty() && token.kind != TokenKind::Merged {
+            let parts: Vec<&str> = token.sub_tokens.iter().map(|t| t.normalized.as_ref()).collect();
+            tooltip.push_str(&format!("\nParts: {}", parts.join(" + ")));
+        }
+
+        if let Some(value) = &token.value {
+            tooltip.push_str(&format!("\nValue: {}", value));
+        }
+        if let Some(locator) = locators.iter().find(|l| l.offset == token.offset) {
+            tooltip.push_str(&format!("\nLocator: {}", locator.label()));
+        }
+        if let Some(lemma) = &token.lemma {
+            tooltip.push_str(&format!("\nLemma: {}", lemma));
+        }
+        if let Some(best) = token.compounds.first() {
+            tooltip.push_str(&format!("\nCompound: {}", best.headwords().join(" + ")));
+        }
+
+        if !token.morphology.is_empty() {
+            let readings: Vec<String> = token.morphology.iter().take(4).map(|a| a.label()).collect();
+            tooltip.push_str(&format!("\nForms: {}", readings.join("; ")));
+        }
+
+        if let Some(verb) = token.verbs.first() {
+            tooltip.push_str(&format!("\nVerb: {}", verb.label()));
+        }
+        let is_main_verb = main_verb == Some(token.id);
+        if is_main_verb {
+            tooltip.push_str("\nMain verb");
+        }
+
+        // Show sub-token boundaries with a middle dot
+        let display = if token.sub_tokens.is_empty() || token.kind == TokenKind::Merged {
+            token.text.to_string()
+        } else {
+            token.sub_tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>().join("\u{00B7}")
+        };
+
+        let expansion = expansions.iter().find(|e| e.token_id == token.id).map(|e| e.text.clone());
+        if let Some(text) = &expansion {
+            tooltip.push_str(&format!("\nExpands to: {}", text));
+        }
+        // Longest repeated passage this token is part of
+        let formula = formulas.iter().find_map(|f| {
+            let k = f.occurrences.iter().position(|o| o.tokens.contains(&token.id))?;
+            Some((f.words.len(), f.occurrences.len(), k, f.occurrences[k].tokens.start == token.id))
+        });
+        if let Some((words, count, _, _)) = formula {
+            tooltip.push_str(&format!("\nFormula: {} words, {} times", words, count));
+        }
+        let folded = formula.filter(|&(_, _, k, _)| k > 0);
+
+        let content = move || match (&expansion, expand.get(), folded, collapse.get()) {
+            (_, _, Some((words, count, _, true)), true) => format!("≈ formula ({} words, ×{})", words, count),
+            (Some(text), true, _, _) => format!("[{}]", text),
+            _ => display.clone(),
+        };
+
+        let forms = token.search_forms().map(|f| f.into_owned());
+        let value = token.value.as_ref().and_then(|v| v.as_integer());
+        let style = move || {
+            // A numeric query also finds number words of that value
+            let hit = query_keys.with(|keys| keys.iter().any(|k| forms.iter().any(|form| loose_match(form, k))))
+                || value.is_some_and(|v| query.with(|q| q.trim().parse() == Ok(v)));
+            let outline = if hit { " outline: 2px solid #ff8c00;" } else { "" };
+            let underline = if is_main_verb { " border-bottom: 3px solid #d35400;" } else { "" };
+            let marking = match (folded, collapse.get()) {
+                (Some((_, _, _, false)), true) => " display: none;",
+                (Some(_), true) => " background-color: #f3e5f5; color: #6a1b9a;",
+                _ if formula.is_some() => " box-shadow: inset 0 -2px 0 #ba68c8;",
+                _ => "",
+            };
+            format!("{} {}{}{}{}{}", base_style, specific_style, outline, underline, marking, squiggle)
+        };
+
+        view! {
+            <span
+                style=style
+                title=tooltip
+            >
+                {content}
+            </span>
+        }
+    }).collect_view()
+}
@@ -1,7 +1,31 @@
 pub mod app;
 pub mod backend {
+    pub mod citation;
+    pub mod compound;
+    pub mod conjugation;
+    pub mod elision;
+    pub mod export;
+    pub mod formula;
+    pub mod lemma;
+    pub mod lexicon;
+    pub mod list_marker;
+    pub mod marker;
+    pub mod morphology;
+    pub mod myanmar;
+    pub mod normalize;
+    pub mod numeral;
+    pub mod orthography;
+    pub mod outline;
+    pub mod particles;
+    pub mod phonotactics;
     pub mod pli_segmenter;
+    pub mod prosody;
+    pub mod reference;
+    pub mod sandhi;
+    pub mod segment_id;
+    pub mod translit;
     pub mod types;
+    pub mod verse;
 }
 pub mod components {
     pub mod input_ui;